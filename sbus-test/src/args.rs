@@ -15,6 +15,14 @@ pub struct Cli {
     /// Network timeout in ms
     #[arg(short, long, default_value = "500", value_parser = parse_duration)]
     pub timeout: Duration,
+
+    /// Number of retransmissions after a timeout
+    #[arg(short, long, default_value = "2")]
+    pub retries: u8,
+
+    /// Serve a simulated station on the host and port and connect to it
+    #[arg(long)]
+    pub simulate: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -142,7 +150,7 @@ pub struct ScanArgs {
     pub min: u8,
 
     /// Maximum station
-    #[arg(default_value = "255")]
+    #[arg(default_value = "254")]
     pub max: u8,
 }
 
@@ -163,6 +171,9 @@ pub enum SetCommands {
         timeout: Duration,
     },
 
+    /// Set number of retransmissions
+    Retries { retries: u8 },
+
     /// Set address offset
     #[command(allow_negative_numbers = true)]
     Offset { offset: i32 },
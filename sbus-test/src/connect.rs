@@ -1,14 +1,14 @@
-use std::{error::Error, io::Write, sync::Arc, time::Duration};
+use std::{error::Error, io::Write, sync::Arc};
 
 use clap::Parser;
 use comfy_table::{presets, CellAlignment, ColumnConstraint, Table, Width};
 use rustyline::{completion::Completer, history::MemHistory, Editor, Helper, Highlighter, Hinter, Validator};
-use sbus::{ieee_to_sbus_float, sbus_float_to_ieee, SBusUDPClient};
+use sbus::{ieee_to_sbus_float, sbus_float_to_ieee, RetryPolicy, SBusClient, SBusError, SBusSimulator, SBusUDPClient, SBusUDPServer};
 use tokio::{join, net::UdpSocket, select, sync::Mutex, time::Instant};
 
 use crate::{
     args::*,
-    util::{timeout_or_cancel, AbortReason, PrettyDisplay},
+    util::{cancellable, Cancelled, PrettyDisplay},
 };
 
 use super::args::{Cli, ExportArgs, ReadArgs};
@@ -16,7 +16,20 @@ use super::args::{Cli, ExportArgs, ReadArgs};
 pub async fn run(args: Cli) -> Result<(), Box<dyn Error>> {
     let host_port = format!("{}:{}", args.host, args.port);
 
-    let mut client = ClientImpl::new(args.timeout, host_port);
+    let _server = if args.simulate {
+        let socket = UdpSocket::bind(&host_port).await?;
+        println!("Simulating station 0 on {}", socket.local_addr()?);
+        Some(SBusUDPServer::new(socket, SBusSimulator::new(0)))
+    } else {
+        None
+    };
+
+    let retry_policy = RetryPolicy {
+        timeout: args.timeout,
+        retries: args.retries,
+    };
+
+    let mut client = ClientImpl::new(retry_policy, host_port);
 
     client.command_loop().await?;
 
@@ -24,7 +37,7 @@ pub async fn run(args: Cli) -> Result<(), Box<dyn Error>> {
 }
 
 struct ClientImpl {
-    timeout: Duration,
+    retry_policy: RetryPolicy,
     host_port: String,
     client: Arc<Mutex<Option<Arc<SBusUDPClient>>>>,
     last_table: Option<Table>,
@@ -33,9 +46,9 @@ struct ClientImpl {
 }
 
 impl ClientImpl {
-    pub fn new(timeout: Duration, host_port: String) -> Self {
+    pub fn new(retry_policy: RetryPolicy, host_port: String) -> Self {
         Self {
-            timeout,
+            retry_policy,
             host_port,
             client: Arc::new(Mutex::new(None)),
             last_table: None,
@@ -50,12 +63,11 @@ impl ClientImpl {
         println!("Attempting to discover station number");
         let start = Instant::now();
 
-        let station = timeout_or_cancel(self.timeout, client.read_sbus_station_number()).await;
+        let station = cancellable(client.read_sbus_station_number()).await;
         match station {
             Ok(Ok(station)) => self.station = station,
             Ok(Err(err)) => println!("Unable to find station number: {err}"),
-            Err(AbortReason::Cancel) => return Ok(()),
-            Err(AbortReason::Timeout) => println!("Unable to find station number: Timeout"),
+            Err(Cancelled) => return Ok(()),
         }
 
         println!("station = {}", self.station);
@@ -125,10 +137,17 @@ impl ClientImpl {
                     return Ok(false);
                 }
                 SetCommands::Timeout { timeout } => {
-                    self.timeout = timeout;
+                    self.retry_policy.timeout = timeout;
+                    self.apply_retry_policy().await;
                     println!("timeout = {}ms", timeout.as_millis());
                     return Ok(false);
                 }
+                SetCommands::Retries { retries } => {
+                    self.retry_policy.retries = retries;
+                    self.apply_retry_policy().await;
+                    println!("retries = {retries}");
+                    return Ok(false);
+                }
             },
             InteractiveCommands::Exit => return Ok(true),
         };
@@ -145,8 +164,8 @@ impl ClientImpl {
         let client = self.connect_if_needed().await?;
 
         let (version, rtc) = join!(
-            timeout_or_cancel(self.timeout, client.read_firmware_version(self.station)),
-            timeout_or_cancel(self.timeout, client.read_real_time_clock(self.station)),
+            cancellable(client.read_firmware_version(self.station)),
+            cancellable(client.read_real_time_clock(self.station)),
         );
         let version = version??;
         let rtc = rtc??;
@@ -172,6 +191,12 @@ impl ClientImpl {
     async fn scan(&self, args: &ScanArgs) -> Result<(), Box<dyn Error>> {
         let client = self.connect_if_needed().await?;
 
+        // Silent stations are expected, waiting for retransmissions would only slow the scan down
+        client.set_retry_policy(RetryPolicy {
+            retries: 0,
+            ..self.retry_policy
+        });
+
         let do_scan = || async {
             let mut table = Table::new();
             table.load_preset(presets::NOTHING);
@@ -187,10 +212,10 @@ impl ClientImpl {
                 let mut table = Table::new();
                 table.load_preset(presets::NOTHING);
 
-                let version: String = match timeout_or_cancel(self.timeout, client.read_firmware_version(station)).await {
-                    Err(reason) => reason.to_string(),
-                    Ok(Ok(version)) => version,
-                    Ok(Err(err)) => return Err(err),
+                let version: String = match client.read_firmware_version(station).await {
+                    Ok(version) => version,
+                    Err(SBusError::Timeout) => SBusError::Timeout.to_string(),
+                    Err(err) => return Err(err),
                 };
 
                 table.add_row([station.to_string(), version]);
@@ -208,13 +233,14 @@ impl ClientImpl {
             _ = tokio::signal::ctrl_c() => {}
         };
 
+        client.set_retry_policy(self.retry_policy);
         Ok(())
     }
 
     async fn read_station(&mut self) -> Result<(), Box<dyn Error>> {
         let client = self.connect_if_needed().await?;
 
-        let station = timeout_or_cancel(self.timeout, client.read_sbus_station_number()).await??;
+        let station = cancellable(client.read_sbus_station_number()).await??;
 
         let mut table = Table::new();
         table.load_preset(presets::NOTHING);
@@ -240,16 +266,16 @@ impl ClientImpl {
 
         let result = match args.kind {
             ReadKind::Counters => {
-                ResultType::Registers(timeout_or_cancel(self.timeout, client.read_counters(self.station, address, args.length)).await??)
+                ResultType::Registers(cancellable(client.read_counters(self.station, address, args.length)).await??)
             }
-            ReadKind::Flags => ResultType::Flags(timeout_or_cancel(self.timeout, client.read_flags(self.station, address, args.length)).await??),
-            ReadKind::Inputs => ResultType::Flags(timeout_or_cancel(self.timeout, client.read_inputs(self.station, address, args.length)).await??),
-            ReadKind::Outputs => ResultType::Flags(timeout_or_cancel(self.timeout, client.read_outputs(self.station, address, args.length)).await??),
+            ReadKind::Flags => ResultType::Flags(cancellable(client.read_flags(self.station, address, args.length)).await??),
+            ReadKind::Inputs => ResultType::Flags(cancellable(client.read_inputs(self.station, address, args.length)).await??),
+            ReadKind::Outputs => ResultType::Flags(cancellable(client.read_outputs(self.station, address, args.length)).await??),
             ReadKind::Registers => {
-                ResultType::Registers(timeout_or_cancel(self.timeout, client.read_registers(self.station, address, args.length)).await??)
+                ResultType::Registers(cancellable(client.read_registers(self.station, address, args.length)).await??)
             }
             ReadKind::Timers => {
-                ResultType::Registers(timeout_or_cancel(self.timeout, client.read_timers(self.station, address, args.length)).await??)
+                ResultType::Registers(cancellable(client.read_timers(self.station, address, args.length)).await??)
             }
         };
 
@@ -277,10 +303,10 @@ impl ClientImpl {
 
                 match args.kind {
                     WriteKind::Flags => {
-                        timeout_or_cancel(self.timeout, client.write_flags(self.station, address, &values)).await??;
+                        cancellable(client.write_flags(self.station, address, &values)).await??;
                     }
                     WriteKind::Outputs => {
-                        timeout_or_cancel(self.timeout, client.write_flags(self.station, address, &values)).await??;
+                        cancellable(client.write_flags(self.station, address, &values)).await??;
                     }
                     _ => panic!("Never"),
                 }
@@ -303,13 +329,13 @@ impl ClientImpl {
 
                 match args.kind {
                     WriteKind::Counters => {
-                        timeout_or_cancel(self.timeout, client.write_counters(self.station, address, &values)).await??;
+                        cancellable(client.write_counters(self.station, address, &values)).await??;
                     }
                     WriteKind::Registers => {
-                        timeout_or_cancel(self.timeout, client.write_registers(self.station, address, &values)).await??;
+                        cancellable(client.write_registers(self.station, address, &values)).await??;
                     }
                     WriteKind::Timers => {
-                        timeout_or_cancel(self.timeout, client.write_timers(self.station, address, &values)).await??;
+                        cancellable(client.write_timers(self.station, address, &values)).await??;
                     }
                     _ => panic!("Never"),
                 }
@@ -418,6 +444,12 @@ impl ClientImpl {
         Ok(())
     }
 
+    async fn apply_retry_policy(&self) {
+        if let Some(client) = self.client.lock().await.as_ref() {
+            client.set_retry_policy(self.retry_policy);
+        }
+    }
+
     async fn connect_if_needed(&self) -> Result<Arc<SBusUDPClient>, Box<dyn Error>> {
         if let Some(client) = self.client.lock().await.as_ref() {
             return Ok(client.clone());
@@ -433,6 +465,7 @@ impl ClientImpl {
         println!();
 
         let (client, handle) = SBusUDPClient::new(socket);
+        client.set_retry_policy(self.retry_policy);
 
         let client = Arc::new(client);
 
@@ -458,7 +491,7 @@ impl ClientImpl {
 
 #[derive(Helper, Hinter, Validator, Highlighter)]
 struct InteractiveHelper {}
-const COMPLETIONS: [&str; 20] = [
+const COMPLETIONS: [&str; 21] = [
     "info",
     "scan ",
     "station",
@@ -475,6 +508,7 @@ const COMPLETIONS: [&str; 20] = [
     "write timers ",
     "set offset ",
     "set timeout ",
+    "set retries ",
     "set station ",
     "export ",
     "help",
@@ -1,32 +1,25 @@
-use std::{error::Error, fmt::Display, future::IntoFuture, time::Duration};
+use std::{error::Error, fmt::Display, future::IntoFuture};
 
 use tokio::select;
 
 #[derive(Debug)]
-pub enum AbortReason {
-    Timeout,
-    Cancel,
-}
+pub struct Cancelled;
 
-impl Display for AbortReason {
+impl Display for Cancelled {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            AbortReason::Timeout => write!(f, "Timeout"),
-            AbortReason::Cancel => write!(f, "Cancel"),
-        }
+        write!(f, "Cancel")
     }
 }
 
-impl Error for AbortReason {}
+impl Error for Cancelled {}
 
-pub async fn timeout_or_cancel<F>(timeout: Duration, future: F) -> Result<F::Output, AbortReason>
+pub async fn cancellable<F>(future: F) -> Result<F::Output, Cancelled>
 where
     F: IntoFuture,
 {
     select! {
         result = future => Ok(result),
-        _ = tokio::time::sleep(timeout) => Err(AbortReason::Timeout),
-        _ = tokio::signal::ctrl_c() => Err(AbortReason::Cancel)
+        _ = tokio::signal::ctrl_c() => Err(Cancelled)
     }
 }
 
@@ -2,13 +2,19 @@ use num_enum::{FromPrimitive, IntoPrimitive};
 
 use crate::encoding::*;
 
+/// The reply of a station to a request that does not return data.
 #[repr(u16)]
 #[derive(Debug, Clone, Copy, PartialEq, IntoPrimitive, FromPrimitive)]
 pub enum Acknowledge {
+    /// The request was executed.
     Ack = 0,
+    /// The request was refused.
     Nak = 1,
+    /// The request was refused because the station is password protected.
     NakPassword = 2,
+    /// The request was refused because the PGU port is in reduced protocol mode.
     NakPGUReducedProtocol = 3,
+    /// The request was refused because the PGU port is already in use.
     NakPGUAlreadyUsed = 4,
     #[num_enum(catch_all)]
     Unknown(u16),
@@ -1,22 +1,8 @@
-use std::{
-    collections::HashMap,
-    error::Error,
-    fmt::Display,
-    sync::{
-        atomic::{AtomicU16, Ordering},
-        Arc,
-    },
-};
-
-use tokio::{
-    net::UdpSocket,
-    sync::{oneshot, Mutex},
-    task::{AbortHandle, JoinHandle},
-};
-
-use crate::{acknowledge::Acknowledge, command_id::CommandId, commands::*, consts::*, encoding::*, message::*, request::Request, RealTimeClock};
-
-/// Errors returned by the [`SBusUDPClient`].
+use std::{collections::HashMap, error::Error, fmt::Display, future::Future, pin::Pin, sync::Arc, task::Poll, time::Duration};
+
+use crate::{acknowledge::Acknowledge, command_id::CommandId, commands::*, consts::*, cpu::CpuSelection, encoding::*, interrupt::Interrupt, media::{Media, MediaValue, MediaValues}, message::TelegramAttribute, multimedia::{validate_multimedia, MultimediaItem, MultimediaRequest, MultimediaResult}, read_plan::ReadPlan, RealTimeClock};
+
+/// Errors returned by an [`SBusClient`].
 #[derive(Debug, Clone)]
 pub enum SBusError {
     /// Represent an IO error.
@@ -29,6 +15,12 @@ pub enum SBusError {
     Internal(&'static str),
     /// Indicates that the response received from the server is not a valid response.
     InvalidResponse(&'static str),
+    /// The server did not respond in time, including all retransmissions.
+    Timeout,
+    /// The server refused the request with the contained acknowledge code.
+    Nak(Acknowledge),
+    /// The request for a chunk of a range failed. The chunks before it may have been completed.
+    Chunk { index: usize, address: u16, error: Box<SBusError> },
 }
 
 impl Display for SBusError {
@@ -38,11 +30,21 @@ impl Display for SBusError {
             SBusError::ArgumentsOutOfRange(err) => write!(f, "Argument out of range: {err}"),
             SBusError::Internal(err) => write!(f, "Internal error: {err}"),
             SBusError::InvalidResponse(err) => write!(f, "Invalid response: {err}"),
+            SBusError::Timeout => write!(f, "Timeout"),
+            SBusError::Nak(ack) => write!(f, "Request refused: {ack:?}"),
+            SBusError::Chunk { index, address, error } => write!(f, "Chunk {index} at address {address} failed: {error}"),
         }
     }
 }
 
-impl Error for SBusError {}
+impl Error for SBusError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SBusError::Chunk { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 impl From<tokio::io::Error> for SBusError {
     fn from(value: tokio::io::Error) -> Self {
@@ -67,329 +69,1394 @@ impl From<EncodeError> for SBusError {
     }
 }
 
-type ResponseResult = Result<Message, SBusError>;
-type ResponseMap = Arc<Mutex<HashMap<u16, oneshot::Sender<ResponseResult>>>>;
+/// Controls how long a client waits for a response and how often a request is retransmitted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Time to wait for a response to each transmission.
+    pub timeout: Duration,
+    /// Number of retransmissions after the first transmission timed out.
+    pub retries: u8,
+}
 
-pub struct SBusUDPClient {
-    socket: Arc<UdpSocket>,
-    sequence_number: AtomicU16,
-    response_map: ResponseMap,
-    abort_handle: AbortHandle,
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_millis(500),
+            retries: 2,
+        }
+    }
 }
 
-impl SBusUDPClient {
-    pub fn new(socket: UdpSocket) -> (Self, JoinHandle<Result<(), SBusError>>) {
-        let socket = Arc::new(socket);
-        let response_map = Arc::new(Mutex::new(HashMap::new()));
+/// The operations supported by every S-Bus client, independent of the transport.
+///
+/// Transports only implement [`SBusClient::send_request`], all other methods are built on top of it.
+/// Implement it yourself to run the same code against a mock or to inject faults.
+pub trait SBusClient: Send + Sync {
+    /// Sends a request with an already encoded body to a station and returns the body of the response.
+    /// Fails with [`SBusError::InvalidResponse`] if the response is not of the expected telegram attribute.
+    /// Requests to the [`BROADCAST_STATION`] are not answered and fail with [`SBusError::ArgumentsOutOfRange`].
+    fn send_request(&self, station: u8, command_id: CommandId, body: Vec<u8>, response_type: TelegramAttribute) -> impl Future<Output = Result<Vec<u8>, SBusError>> + Send;
 
-        let join_handle = tokio::spawn(Self::receive_response(socket.clone(), response_map.clone()));
+    /// Sends a request with an already encoded body to all stations, without waiting for a reply.
+    ///
+    /// Broadcasts are not confirmed: stations never reply to them, so a station that missed or refused the request goes unnoticed.
+    /// Succeeding only means that the request was sent.
+    #[allow(unused_variables)]
+    fn send_broadcast(&self, command_id: CommandId, body: Vec<u8>) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async { Err(SBusError::Internal("Broadcasts are not supported by the client")) }
+    }
 
-        let client = Self {
-            socket,
-            sequence_number: AtomicU16::default(),
-            response_map,
-            abort_handle: join_handle.abort_handle(),
-        };
+    fn read_real_time_clock(&self, station: u8) -> impl Future<Output = Result<RealTimeClock, SBusError>> + Send {
+        async move {
+            let res_body = self
+                .send_request(station, CommandId::ReadRealTimeClock, vec![], TelegramAttribute::Response)
+                .await?;
+            let res = ReadRealTimeClockResponse::decode_from_bytes(&res_body)?;
+            Ok(res.rtc)
+        }
+    }
 
-        (client, join_handle)
+    fn read_display_register(&self, station: u8) -> impl Future<Output = Result<u32, SBusError>> + Send {
+        async move {
+            let res_body = self
+                .send_request(station, CommandId::ReadDisplayRegister, vec![], TelegramAttribute::Response)
+                .await?;
+            let res = ReadDisplayRegisterResponse::decode_from_bytes(&res_body)?;
+            Ok(res.register)
+        }
     }
 
-    pub async fn read_real_time_clock(&self, station: u8) -> Result<RealTimeClock, SBusError> {
-        let res_body = self
-            .send_request(station, CommandId::ReadRealTimeClock, vec![], TelegramAttribute::Response)
-            .await?;
-        let res = ReadRealTimeClockResponse::decode_from_bytes(&res_body)?;
-        Ok(res.rtc)
+    fn read_firmware_version(&self, station: u8) -> impl Future<Output = Result<String, SBusError>> + Send {
+        async move {
+            let res_body = self
+                .send_request(station, CommandId::ReadFirmwareVersion, vec![], TelegramAttribute::Response)
+                .await?;
+            let res = ReadFirmwareVersionResponse::decode_from_bytes(&res_body)?;
+            Ok(res.version.into())
+        }
     }
 
-    pub async fn read_display_register(&self, station: u8) -> Result<u32, SBusError> {
-        let res_body = self
-            .send_request(station, CommandId::ReadDisplayRegister, vec![], TelegramAttribute::Response)
-            .await?;
-        let res = ReadDisplayRegisterResponse::decode_from_bytes(&res_body)?;
-        Ok(res.register)
+    fn read_sbus_station_number(&self) -> impl Future<Output = Result<u8, SBusError>> + Send {
+        async move {
+            let res_body = self
+                .send_request(254, CommandId::ReadSBusStationNumber, vec![], TelegramAttribute::Response)
+                .await?;
+            let res = ReadSBusStationNumberResponse::decode_from_bytes(&res_body)?;
+            Ok(res.station)
+        }
     }
 
-    pub async fn read_firmware_version(&self, station: u8) -> Result<String, SBusError> {
-        let res_body = self
-            .send_request(station, CommandId::ReadFirmwareVersion, vec![], TelegramAttribute::Response)
-            .await?;
-        let res = ReadFirmwareVersionResponse::decode_from_bytes(&res_body)?;
-        Ok(res.version.into())
+    /// Reads the halt failure register, which holds the cause of the last CPU halt.
+    fn read_halt_failure_register(&self, station: u8) -> impl Future<Output = Result<u32, SBusError>> + Send {
+        async move {
+            let res_body = self
+                .send_request(station, CommandId::ReadHaltFailureRegister, vec![], TelegramAttribute::Response)
+                .await?;
+            let res = ReadHaltFailureRegisterResponse::decode_from_bytes(&res_body)?;
+            Ok(res.register)
+        }
     }
 
-    pub async fn read_sbus_station_number(&self) -> Result<u8, SBusError> {
-        let res_body = self
-            .send_request(254, CommandId::ReadSBusStationNumber, vec![], TelegramAttribute::Response)
-            .await?;
-        let res = ReadSBusStationNumberResponse::decode_from_bytes(&res_body)?;
-        Ok(res.station)
+    /// Clears the history table and the halt failure register.
+    fn clear_history(&self, station: u8) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self.send_request(station, CommandId::ClearHistoryFailure, vec![], TelegramAttribute::Acknowledge).await?;
+            acknowledge_result(&res_body)
+        }
     }
 
-    pub async fn read_counters(&self, station: u8, address: u16, length: u8) -> Result<Vec<i32>, SBusError> {
-        validate_input(address, length as usize, COUNTERS_MAX_REQUEST_LEN)?;
-        let res_body = self
-            .send_request(
-                station,
-                CommandId::ReadCounters,
-                ReadCountersRequest { address, length }.encode_to_bytes()?,
-                TelegramAttribute::Response,
-            )
-            .await?;
-        let res = ReadCountersResponse::decode_from_bytes(&res_body)?;
-        Ok(res.values.into())
+    fn read_counters(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<i32>, SBusError>> + Send {
+        async move {
+            validate_input(address, length as usize, COUNTERS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::ReadCounters,
+                    ReadCountersRequest { address, length }.encode_to_bytes()?,
+                    TelegramAttribute::Response,
+                )
+                .await?;
+            let res = ReadCountersResponse::decode_from_bytes(&res_body)?;
+            Ok(res.values.into())
+        }
     }
 
-    pub async fn read_flags(&self, station: u8, address: u16, length: u8) -> Result<Vec<bool>, SBusError> {
-        validate_input(address, length as usize, FLAGS_MAX_REQUEST_LEN)?;
-        let res_body = self
-            .send_request(
-                station,
-                CommandId::ReadFlags,
-                ReadFlagsRequest { address, length }.encode_to_bytes()?,
-                TelegramAttribute::Response,
-            )
-            .await?;
-        let res = ReadFlagsResponse::decode_from_bytes(&res_body)?;
-        Ok(res.values.into())
+    fn read_flags(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<bool>, SBusError>> + Send {
+        async move {
+            validate_input(address, length as usize, FLAGS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::ReadFlags,
+                    ReadFlagsRequest { address, length }.encode_to_bytes()?,
+                    TelegramAttribute::Response,
+                )
+                .await?;
+            let res = ReadFlagsResponse::decode_from_bytes(&res_body)?;
+            Ok(res.values.into())
+        }
     }
 
-    pub async fn read_inputs(&self, station: u8, address: u16, length: u8) -> Result<Vec<bool>, SBusError> {
-        validate_input(address, length as usize, INPUTS_MAX_REQUEST_LEN)?;
-        let res_body = self
-            .send_request(
-                station,
-                CommandId::ReadInputs,
-                ReadInputsRequest { address, length }.encode_to_bytes()?,
-                TelegramAttribute::Response,
-            )
-            .await?;
-        let res = ReadInputsResponse::decode_from_bytes(&res_body)?;
-        Ok(res.values.into())
+    fn read_inputs(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<bool>, SBusError>> + Send {
+        async move {
+            validate_input(address, length as usize, INPUTS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::ReadInputs,
+                    ReadInputsRequest { address, length }.encode_to_bytes()?,
+                    TelegramAttribute::Response,
+                )
+                .await?;
+            let res = ReadInputsResponse::decode_from_bytes(&res_body)?;
+            Ok(res.values.into())
+        }
     }
 
-    pub async fn read_outputs(&self, station: u8, address: u16, length: u8) -> Result<Vec<bool>, SBusError> {
-        validate_input(address, length as usize, OUTPUTS_MAX_REQUEST_LEN)?;
-        let res_body = self
-            .send_request(
-                station,
-                CommandId::ReadOutputs,
-                ReadOutputsRequest { address, length }.encode_to_bytes()?,
-                TelegramAttribute::Response,
-            )
-            .await?;
-        let res = ReadOutputsResponse::decode_from_bytes(&res_body)?;
-        Ok(res.values.into())
+    fn read_outputs(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<bool>, SBusError>> + Send {
+        async move {
+            validate_input(address, length as usize, OUTPUTS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::ReadOutputs,
+                    ReadOutputsRequest { address, length }.encode_to_bytes()?,
+                    TelegramAttribute::Response,
+                )
+                .await?;
+            let res = ReadOutputsResponse::decode_from_bytes(&res_body)?;
+            Ok(res.values.into())
+        }
     }
 
-    pub async fn read_registers(&self, station: u8, address: u16, length: u8) -> Result<Vec<i32>, SBusError> {
-        validate_input(address, length as usize, REGISTERS_MAX_REQUEST_LEN)?;
-        let res_body = self
-            .send_request(
-                station,
-                CommandId::ReadRegisters,
-                ReadRegistersRequest { address, length }.encode_to_bytes()?,
-                TelegramAttribute::Response,
-            )
-            .await?;
-        let res = ReadRegistersResponse::decode_from_bytes(&res_body)?;
-        Ok(res.values.into())
+    fn read_registers(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<i32>, SBusError>> + Send {
+        async move {
+            validate_input(address, length as usize, REGISTERS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::ReadRegisters,
+                    ReadRegistersRequest { address, length }.encode_to_bytes()?,
+                    TelegramAttribute::Response,
+                )
+                .await?;
+            let res = ReadRegistersResponse::decode_from_bytes(&res_body)?;
+            Ok(res.values.into())
+        }
     }
 
-    pub async fn read_timers(&self, station: u8, address: u16, length: u8) -> Result<Vec<i32>, SBusError> {
-        validate_input(address, length as usize, TIMERS_MAX_REQUEST_LEN)?;
-        let res_body = self
-            .send_request(
-                station,
-                CommandId::ReadTimers,
-                ReadTimersRequest { address, length }.encode_to_bytes()?,
-                TelegramAttribute::Response,
-            )
-            .await?;
-        let res = ReadTimersResponse::decode_from_bytes(&res_body)?;
-        Ok(res.values.into())
+    fn read_timers(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<i32>, SBusError>> + Send {
+        async move {
+            validate_input(address, length as usize, TIMERS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::ReadTimers,
+                    ReadTimersRequest { address, length }.encode_to_bytes()?,
+                    TelegramAttribute::Response,
+                )
+                .await?;
+            let res = ReadTimersResponse::decode_from_bytes(&res_body)?;
+            Ok(res.values.into())
+        }
     }
 
-    pub async fn write_real_time_clock(&self, station: u8, rtc: RealTimeClock) -> Result<bool, SBusError> {
-        let res_body = self
-            .send_request(
-                station,
-                CommandId::WriteRealTimeClock,
-                WriteRealTimeClockRequest { rtc }.encode_to_bytes()?,
-                TelegramAttribute::Acknowledge,
-            )
-            .await?;
-        let res = Acknowledge::decode_from_bytes(&res_body)?;
-        Ok(res == Acknowledge::Ack)
+    /// Reads user EEPROM registers, which keep their values without a battery.
+    fn read_user_eeprom_registers(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<i32>, SBusError>> + Send {
+        async move {
+            validate_input(address, length as usize, USER_EEPROM_REGISTERS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::ReadUserEEPROMRegister,
+                    ReadUserEepromRegistersRequest { address, length }.encode_to_bytes()?,
+                    TelegramAttribute::Response,
+                )
+                .await?;
+            let res = ReadUserEepromRegistersResponse::decode_from_bytes(&res_body)?;
+            Ok(res.values.into())
+        }
     }
 
-    pub async fn write_counters(&self, station: u8, address: u16, values: &[i32]) -> Result<bool, SBusError> {
-        validate_input(address, values.len(), COUNTERS_MAX_REQUEST_LEN)?;
-        let res_body = self
-            .send_request(
-                station,
-                CommandId::WriteCounters,
-                WriteCountersRequest {
-                    address,
-                    values: values.into(),
-                }
-                .encode_to_bytes()?,
-                TelegramAttribute::Acknowledge,
-            )
-            .await?;
-        let res = Acknowledge::decode_from_bytes(&res_body)?;
-        Ok(res == Acknowledge::Ack)
+    fn write_real_time_clock(&self, station: u8, rtc: RealTimeClock) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::WriteRealTimeClock,
+                    WriteRealTimeClockRequest { rtc }.encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
     }
 
-    pub async fn write_flags(&self, station: u8, address: u16, values: &[bool]) -> Result<bool, SBusError> {
-        validate_input(address, values.len(), FLAGS_MAX_REQUEST_LEN)?;
-        let res_body = self
-            .send_request(
-                station,
-                CommandId::WriteFlags,
-                WriteFlagsRequest {
-                    address,
-                    values: values.into(),
+    fn write_counters(&self, station: u8, address: u16, values: &[i32]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), COUNTERS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::WriteCounters,
+                    WriteCountersRequest {
+                        address,
+                        values: values.into(),
+                    }
+                    .encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    fn write_flags(&self, station: u8, address: u16, values: &[bool]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), FLAGS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::WriteFlags,
+                    WriteFlagsRequest {
+                        address,
+                        values: values.into(),
+                    }
+                    .encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    fn write_outputs(&self, station: u8, address: u16, values: &[bool]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), OUTPUTS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::WriteOutputs,
+                    WriteOutputsRequest {
+                        address,
+                        values: values.into(),
+                    }
+                    .encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    fn write_registers(&self, station: u8, address: u16, values: &[i32]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), REGISTERS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::WriteRegisters,
+                    WriteRegistersRequest {
+                        address,
+                        values: values.into(),
+                    }
+                    .encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    fn write_timers(&self, station: u8, address: u16, values: &[i32]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), TIMERS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::WriteTimers,
+                    WriteTimersRequest {
+                        address,
+                        values: values.into(),
+                    }
+                    .encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Sets the real-time clock of all stations at once, see [`SBusClient::send_broadcast`].
+    fn broadcast_write_real_time_clock(&self, rtc: RealTimeClock) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move { self.send_broadcast(CommandId::WriteRealTimeClock, WriteRealTimeClockRequest { rtc }.encode_to_bytes()?).await }
+    }
+
+    /// Writes counters of all stations at once, see [`SBusClient::send_broadcast`].
+    fn broadcast_write_counters(&self, address: u16, values: &[i32]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), COUNTERS_MAX_REQUEST_LEN)?;
+            let req_body = WriteCountersRequest {
+                address,
+                values: values.into(),
+            }
+            .encode_to_bytes()?;
+            self.send_broadcast(CommandId::WriteCounters, req_body).await
+        }
+    }
+
+    /// Writes flags of all stations at once, see [`SBusClient::send_broadcast`].
+    fn broadcast_write_flags(&self, address: u16, values: &[bool]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), FLAGS_MAX_REQUEST_LEN)?;
+            let req_body = WriteFlagsRequest {
+                address,
+                values: values.into(),
+            }
+            .encode_to_bytes()?;
+            self.send_broadcast(CommandId::WriteFlags, req_body).await
+        }
+    }
+
+    /// Writes outputs of all stations at once, see [`SBusClient::send_broadcast`].
+    fn broadcast_write_outputs(&self, address: u16, values: &[bool]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), OUTPUTS_MAX_REQUEST_LEN)?;
+            let req_body = WriteOutputsRequest {
+                address,
+                values: values.into(),
+            }
+            .encode_to_bytes()?;
+            self.send_broadcast(CommandId::WriteOutputs, req_body).await
+        }
+    }
+
+    /// Writes registers of all stations at once, see [`SBusClient::send_broadcast`].
+    fn broadcast_write_registers(&self, address: u16, values: &[i32]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), REGISTERS_MAX_REQUEST_LEN)?;
+            let req_body = WriteRegistersRequest {
+                address,
+                values: values.into(),
+            }
+            .encode_to_bytes()?;
+            self.send_broadcast(CommandId::WriteRegisters, req_body).await
+        }
+    }
+
+    /// Writes timers of all stations at once, see [`SBusClient::send_broadcast`].
+    fn broadcast_write_timers(&self, address: u16, values: &[i32]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), TIMERS_MAX_REQUEST_LEN)?;
+            let req_body = WriteTimersRequest {
+                address,
+                values: values.into(),
+            }
+            .encode_to_bytes()?;
+            self.send_broadcast(CommandId::WriteTimers, req_body).await
+        }
+    }
+
+    /// Reads consecutive elements of any media.
+    fn read_media(&self, station: u8, media: Media, address: u16, length: u8) -> impl Future<Output = Result<MediaValues, SBusError>> + Send {
+        async move {
+            // Bits are transferred in whole bytes, the padding is not returned
+            let bits = |mut values: Vec<bool>| {
+                values.truncate(length as usize);
+                MediaValues::Bools(values)
+            };
+            Ok(match media {
+                Media::Counters => MediaValues::Integers(self.read_counters(station, address, length).await?),
+                Media::Flags => bits(self.read_flags(station, address, length).await?),
+                Media::Inputs => bits(self.read_inputs(station, address, length).await?),
+                Media::Outputs => bits(self.read_outputs(station, address, length).await?),
+                Media::Registers => MediaValues::Integers(self.read_registers(station, address, length).await?),
+                Media::Timers => MediaValues::Integers(self.read_timers(station, address, length).await?),
+            })
+        }
+    }
+
+    /// Writes consecutive elements of any media except inputs.
+    fn write_media(&self, station: u8, media: Media, address: u16, values: &MediaValues) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            match (media, values) {
+                (Media::Counters, MediaValues::Integers(values)) => self.write_counters(station, address, values).await,
+                (Media::Flags, MediaValues::Bools(values)) => self.write_flags(station, address, values).await,
+                (Media::Outputs, MediaValues::Bools(values)) => self.write_outputs(station, address, values).await,
+                (Media::Registers, MediaValues::Integers(values)) => self.write_registers(station, address, values).await,
+                (Media::Timers, MediaValues::Integers(values)) => self.write_timers(station, address, values).await,
+                (Media::Inputs, _) => Err(SBusError::ArgumentsOutOfRange("Inputs can not be written")),
+                _ => Err(SBusError::ArgumentsOutOfRange("Values do not match the media")),
+            }
+        }
+    }
+
+    /// Reads a range of any length, split into as many requests as the request limit of the media requires.
+    /// Up to `pipeline` requests are in flight at once, 1 sends them one after another.
+    /// If a request fails, the whole read fails with [`SBusError::Chunk`].
+    fn read_media_range(&self, station: u8, media: Media, address: u16, length: usize, pipeline: usize) -> impl Future<Output = Result<MediaValues, SBusError>> + Send {
+        async move {
+            validate_input(address, length, u16::MAX)?;
+            let chunks = split_range(address, length, media.max_request_len());
+            let reads = chunks.iter().map(|&(address, length)| async move {
+                let values = self.read_media(station, media, address, length as u8).await?;
+                if values.len() != length {
+                    return Err(SBusError::InvalidResponse("Unexpected number of values"));
                 }
-                .encode_to_bytes()?,
-                TelegramAttribute::Acknowledge,
-            )
-            .await?;
-        let res = Acknowledge::decode_from_bytes(&res_body)?;
-        Ok(res == Acknowledge::Ack)
+                Ok(values)
+            });
+            let mut values = MediaValues::new(media);
+            for chunk in join_chunks(&chunks, reads, pipeline).await? {
+                values.append(chunk);
+            }
+            Ok(values)
+        }
     }
 
-    pub async fn write_outputs(&self, station: u8, address: u16, values: &[bool]) -> Result<bool, SBusError> {
-        validate_input(address, values.len(), OUTPUTS_MAX_REQUEST_LEN)?;
-        let res_body = self
-            .send_request(
-                station,
-                CommandId::WriteOutputs,
-                WriteOutputsRequest {
-                    address,
-                    values: values.into(),
+    /// Writes a range of any length, split into as many requests as the request limit of the media requires.
+    /// Up to `pipeline` requests are in flight at once, 1 sends them one after another.
+    /// If a request fails, the write fails with [`SBusError::Chunk`] and the range may be written partially.
+    fn write_media_range(&self, station: u8, media: Media, address: u16, values: &MediaValues, pipeline: usize) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), u16::MAX)?;
+            if media == Media::Inputs {
+                return Err(SBusError::ArgumentsOutOfRange("Inputs can not be written"));
+            }
+            if !values.fits(media) {
+                return Err(SBusError::ArgumentsOutOfRange("Values do not match the media"));
+            }
+            let chunks = split_range(address, values.len(), media.max_request_len());
+            let writes = chunks.iter().map(|&(chunk_address, length)| {
+                let offset = (chunk_address - address) as usize;
+                let values = values.slice(offset..offset + length);
+                async move { self.write_media(station, media, chunk_address, &values).await }
+            });
+            join_chunks(&chunks, writes, pipeline).await?;
+            Ok(())
+        }
+    }
+
+    /// Reads counters like [`SBusClient::read_media_range`], one request after another.
+    fn read_counters_range(&self, station: u8, address: u16, length: usize) -> impl Future<Output = Result<Vec<i32>, SBusError>> + Send {
+        async move {
+            let values = self.read_media_range(station, Media::Counters, address, length, 1).await?;
+            values.into_integers().ok_or(SBusError::Internal("Unexpected media values"))
+        }
+    }
+
+    /// Reads flags like [`SBusClient::read_media_range`], one request after another.
+    fn read_flags_range(&self, station: u8, address: u16, length: usize) -> impl Future<Output = Result<Vec<bool>, SBusError>> + Send {
+        async move {
+            let values = self.read_media_range(station, Media::Flags, address, length, 1).await?;
+            values.into_bools().ok_or(SBusError::Internal("Unexpected media values"))
+        }
+    }
+
+    /// Reads inputs like [`SBusClient::read_media_range`], one request after another.
+    fn read_inputs_range(&self, station: u8, address: u16, length: usize) -> impl Future<Output = Result<Vec<bool>, SBusError>> + Send {
+        async move {
+            let values = self.read_media_range(station, Media::Inputs, address, length, 1).await?;
+            values.into_bools().ok_or(SBusError::Internal("Unexpected media values"))
+        }
+    }
+
+    /// Reads outputs like [`SBusClient::read_media_range`], one request after another.
+    fn read_outputs_range(&self, station: u8, address: u16, length: usize) -> impl Future<Output = Result<Vec<bool>, SBusError>> + Send {
+        async move {
+            let values = self.read_media_range(station, Media::Outputs, address, length, 1).await?;
+            values.into_bools().ok_or(SBusError::Internal("Unexpected media values"))
+        }
+    }
+
+    /// Reads registers like [`SBusClient::read_media_range`], one request after another.
+    fn read_registers_range(&self, station: u8, address: u16, length: usize) -> impl Future<Output = Result<Vec<i32>, SBusError>> + Send {
+        async move {
+            let values = self.read_media_range(station, Media::Registers, address, length, 1).await?;
+            values.into_integers().ok_or(SBusError::Internal("Unexpected media values"))
+        }
+    }
+
+    /// Reads timers like [`SBusClient::read_media_range`], one request after another.
+    fn read_timers_range(&self, station: u8, address: u16, length: usize) -> impl Future<Output = Result<Vec<i32>, SBusError>> + Send {
+        async move {
+            let values = self.read_media_range(station, Media::Timers, address, length, 1).await?;
+            values.into_integers().ok_or(SBusError::Internal("Unexpected media values"))
+        }
+    }
+
+    /// Writes counters like [`SBusClient::write_media_range`], one request after another.
+    fn write_counters_range(&self, station: u8, address: u16, values: &[i32]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move { self.write_media_range(station, Media::Counters, address, &MediaValues::Integers(values.into()), 1).await }
+    }
+
+    /// Writes flags like [`SBusClient::write_media_range`], one request after another.
+    fn write_flags_range(&self, station: u8, address: u16, values: &[bool]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move { self.write_media_range(station, Media::Flags, address, &MediaValues::Bools(values.into()), 1).await }
+    }
+
+    /// Writes outputs like [`SBusClient::write_media_range`], one request after another.
+    fn write_outputs_range(&self, station: u8, address: u16, values: &[bool]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move { self.write_media_range(station, Media::Outputs, address, &MediaValues::Bools(values.into()), 1).await }
+    }
+
+    /// Writes registers like [`SBusClient::write_media_range`], one request after another.
+    fn write_registers_range(&self, station: u8, address: u16, values: &[i32]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move { self.write_media_range(station, Media::Registers, address, &MediaValues::Integers(values.into()), 1).await }
+    }
+
+    /// Writes timers like [`SBusClient::write_media_range`], one request after another.
+    fn write_timers_range(&self, station: u8, address: u16, values: &[i32]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move { self.write_media_range(station, Media::Timers, address, &MediaValues::Integers(values.into()), 1).await }
+    }
+
+    /// Executes the reads of the plan and returns the value of every planned element.
+    /// Up to `pipeline` requests are in flight at once, 1 sends them one after another.
+    /// If a request fails, the whole plan fails with [`SBusError::Chunk`], indexed by [`ReadPlan::reads`].
+    fn read_planned(&self, station: u8, plan: &ReadPlan, pipeline: usize) -> impl Future<Output = Result<HashMap<(Media, u16), MediaValue>, SBusError>> + Send {
+        async move {
+            let chunks: Vec<(u16, usize)> = plan.reads().iter().map(|read| (read.address, read.length as usize)).collect();
+            let reads = plan.reads().iter().map(|read| async move {
+                let values = self.read_media(station, read.media, read.address, read.length).await?;
+                if values.len() != read.length as usize {
+                    return Err(SBusError::InvalidResponse("Unexpected number of values"));
                 }
-                .encode_to_bytes()?,
-                TelegramAttribute::Acknowledge,
-            )
-            .await?;
-        let res = Acknowledge::decode_from_bytes(&res_body)?;
-        Ok(res == Acknowledge::Ack)
+                Ok(values)
+            });
+            let results = join_chunks(&chunks, reads, pipeline).await?;
+
+            // Both the items and the reads are sorted by media and address
+            let mut table = HashMap::new();
+            let mut reads = plan.reads().iter().zip(results).peekable();
+            for (media, address) in plan.items() {
+                while let Some((read, values)) = reads.peek() {
+                    if read.media == media && address >= read.address {
+                        if let Some(value) = values.get((address - read.address) as usize) {
+                            table.insert((media, address), value);
+                            break;
+                        }
+                    }
+                    reads.next();
+                }
+            }
+            Ok(table)
+        }
     }
 
-    pub async fn write_registers(&self, station: u8, address: u16, values: &[i32]) -> Result<bool, SBusError> {
-        validate_input(address, values.len(), REGISTERS_MAX_REQUEST_LEN)?;
-        let res_body = self
-            .send_request(
-                station,
-                CommandId::WriteRegisters,
-                WriteRegistersRequest {
-                    address,
-                    values: values.into(),
+    /// Reads and writes the items of the request, one request per item in the order of the items.
+    /// Returns one result per item. An item the station refuses is returned as [`MultimediaResult::Refused`] and does not stop the other items.
+    ///
+    /// Nothing is sent if one of the items is invalid.
+    fn read_write_multimedia(&self, station: u8, request: &MultimediaRequest) -> impl Future<Output = Result<Vec<MultimediaResult>, SBusError>> + Send {
+        async move {
+            let items = request.items();
+            validate_multimedia(items)?;
+            let mut results = Vec::with_capacity(items.len());
+            for item in items {
+                let result = match item {
+                    MultimediaItem::Read { media, address, length } => self.read_media(station, *media, *address, *length).await.map(MultimediaResult::Values),
+                    MultimediaItem::Write { media, address, values } => self.write_media(station, *media, *address, values).await.map(|_| MultimediaResult::Written),
+                };
+                results.push(match result {
+                    Err(SBusError::Nak(ack)) => MultimediaResult::Refused(ack),
+                    result => result?,
+                });
+            }
+            Ok(results)
+        }
+    }
+
+    /// Writes user EEPROM registers, which keep their values without a battery.
+    fn write_user_eeprom_registers(&self, station: u8, address: u16, values: &[i32]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(address, values.len(), USER_EEPROM_REGISTERS_MAX_REQUEST_LEN)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::WriteUserEEPROMRegister,
+                    WriteUserEepromRegistersRequest {
+                        address,
+                        values: values.into(),
+                    }
+                    .encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Sets all flags, outputs, registers and timers to zero.
+    fn clear_all_fort(&self, station: u8) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self.send_request(station, CommandId::ClearAllFORT, vec![], TelegramAttribute::Acknowledge).await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Sets all flags to zero.
+    fn clear_flags(&self, station: u8) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self.send_request(station, CommandId::ClearFlags, vec![], TelegramAttribute::Acknowledge).await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Sets all outputs to zero.
+    fn clear_outputs(&self, station: u8) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self.send_request(station, CommandId::ClearOutputs, vec![], TelegramAttribute::Acknowledge).await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Sets all registers to zero.
+    fn clear_registers(&self, station: u8) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self.send_request(station, CommandId::ClearRegisters, vec![], TelegramAttribute::Acknowledge).await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Sets all timers to zero.
+    fn clear_timers(&self, station: u8) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self.send_request(station, CommandId::ClearTimers, vec![], TelegramAttribute::Acknowledge).await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Starts executing the user program on the selected CPUs.
+    fn run_cpu(&self, station: u8, cpus: impl Into<CpuSelection>) -> impl Future<Output = Result<(), SBusError>> + Send {
+        let cpus = cpus.into();
+        async move {
+            let res_body = self.send_request(station, cpus.run_command(), vec![], TelegramAttribute::Acknowledge).await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Stops executing the user program on the selected CPUs.
+    fn stop_cpu(&self, station: u8, cpus: impl Into<CpuSelection>) -> impl Future<Output = Result<(), SBusError>> + Send {
+        let cpus = cpus.into();
+        async move {
+            let res_body = self.send_request(station, cpus.stop_command(), vec![], TelegramAttribute::Acknowledge).await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Restarts the selected CPUs with a cold start, as after switching the PCD on.
+    /// Fails with [`SBusError::ArgumentsOutOfRange`] for CPU 0, which can only be restarted with all CPUs.
+    fn restart_cpu_cold(&self, station: u8, cpus: impl Into<CpuSelection>) -> impl Future<Output = Result<(), SBusError>> + Send {
+        let cpus = cpus.into();
+        async move {
+            let command_id = cpus.restart_cold_command().ok_or(SBusError::ArgumentsOutOfRange("CPU 0 can not be restarted individually"))?;
+            let res_body = self.send_request(station, command_id, vec![], TelegramAttribute::Acknowledge).await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Restarts the selected CPUs with a warm start.
+    /// Fails with [`SBusError::ArgumentsOutOfRange`] for CPU 0, which can only be restarted with all CPUs.
+    fn restart_cpu_warm(&self, station: u8, cpus: impl Into<CpuSelection>) -> impl Future<Output = Result<(), SBusError>> + Send {
+        let cpus = cpus.into();
+        async move {
+            let command_id = cpus.restart_warm_command().ok_or(SBusError::ArgumentsOutOfRange("CPU 0 can not be restarted individually"))?;
+            let res_body = self.send_request(station, command_id, vec![], TelegramAttribute::Acknowledge).await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Triggers the interrupt, which executes the XOB in the user program.
+    fn trigger_interrupt(&self, station: u8, interrupt: Interrupt) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self.send_request(station, interrupt.command(), vec![], TelegramAttribute::Acknowledge).await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Reads elements of a data block, split into as many telegrams as necessary.
+    fn read_data_block(&self, station: u8, block: u16, offset: u16, length: usize) -> impl Future<Output = Result<Vec<i32>, SBusError>> + Send {
+        read_data_block_chunks(self, station, CommandId::ReadDataBlock, block, offset, length)
+    }
+
+    /// Reads elements of an extended data block (DBX), split into as many telegrams as necessary.
+    fn read_dbx(&self, station: u8, block: u16, offset: u16, length: usize) -> impl Future<Output = Result<Vec<i32>, SBusError>> + Send {
+        read_data_block_chunks(self, station, CommandId::ReadDBX, block, offset, length)
+    }
+
+    /// Writes elements of a data block, split into as many telegrams as necessary.
+    /// Stops at the first telegram that fails, the telegrams before it are already written.
+    fn write_data_block(&self, station: u8, block: u16, offset: u16, values: &[i32]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_input(offset, values.len(), u16::MAX)?;
+            for (index, chunk) in values.chunks(DATA_BLOCK_MAX_REQUEST_LEN as usize).enumerate() {
+                let res_body = self
+                    .send_request(
+                        station,
+                        CommandId::WriteDataBlock,
+                        WriteDataBlockRequest {
+                            block,
+                            offset: offset + (index * DATA_BLOCK_MAX_REQUEST_LEN as usize) as u16,
+                            values: chunk.into(),
+                        }
+                        .encode_to_bytes()?,
+                        TelegramAttribute::Acknowledge,
+                    )
+                    .await?;
+                acknowledge_result(&res_body)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Creates a data block with the given number of elements.
+    fn make_data_block(&self, station: u8, block: u16, length: u16) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::MakeDataBlock,
+                    MakeDataBlockRequest { block, length }.encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Sets all elements of a data block to zero.
+    fn clear_data_block(&self, station: u8, block: u16) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::ClearDataBlock,
+                    ClearDataBlockRequest { block }.encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Reads characters of a text, split into as many telegrams as necessary.
+    fn read_text(&self, station: u8, text: u16, offset: u16, length: usize) -> impl Future<Output = Result<String, SBusError>> + Send {
+        async move {
+            validate_input(offset, length, u16::MAX)?;
+            let mut value = String::with_capacity(length);
+            let mut position = 0;
+            while position < length {
+                let chunk_length = usize::min(length - position, TEXT_MAX_REQUEST_LEN as usize);
+                let res_body = self
+                    .send_request(
+                        station,
+                        CommandId::ReadText,
+                        ReadTextRequest {
+                            text,
+                            offset: offset + position as u16,
+                            length: chunk_length as u8,
+                        }
+                        .encode_to_bytes()?,
+                        TelegramAttribute::Response,
+                    )
+                    .await?;
+                let res = ReadTextResponse::decode_from_bytes(&res_body)?;
+                if res.value.chars().count() != chunk_length {
+                    return Err(SBusError::InvalidResponse("Unexpected number of characters"));
                 }
-                .encode_to_bytes()?,
-                TelegramAttribute::Acknowledge,
-            )
-            .await?;
-        let res = Acknowledge::decode_from_bytes(&res_body)?;
-        Ok(res == Acknowledge::Ack)
+                value.push_str(&res.value);
+                position += chunk_length;
+            }
+            Ok(value)
+        }
+    }
+
+    /// Writes characters of a text, split into as many telegrams as necessary.
+    /// The text must only contain characters of ISO 8859-1.
+    /// Stops at the first telegram that fails, the telegrams before it are already written.
+    fn write_text(&self, station: u8, text: u16, offset: u16, value: &str) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let chars: Vec<char> = value.chars().collect();
+            validate_input(offset, chars.len(), u16::MAX)?;
+            if chars.iter().any(|c| u32::from(*c) > 0xFF) {
+                return Err(SBusError::ArgumentsOutOfRange("Text contains characters outside of ISO 8859-1"));
+            }
+            for (index, chunk) in chars.chunks(TEXT_MAX_REQUEST_LEN as usize).enumerate() {
+                let res_body = self
+                    .send_request(
+                        station,
+                        CommandId::WriteText,
+                        WriteTextRequest {
+                            text,
+                            offset: offset + (index * TEXT_MAX_REQUEST_LEN as usize) as u16,
+                            value: chunk.iter().collect::<String>().into(),
+                        }
+                        .encode_to_bytes()?,
+                        TelegramAttribute::Acknowledge,
+                    )
+                    .await?;
+                acknowledge_result(&res_body)?;
+            }
+            Ok(())
+        }
     }
 
-    pub async fn write_timers(&self, station: u8, address: u16, values: &[i32]) -> Result<bool, SBusError> {
-        validate_input(address, values.len(), TIMERS_MAX_REQUEST_LEN)?;
-        let res_body = self
+    /// Creates a text with the given number of characters.
+    fn make_text(&self, station: u8, text: u16, length: u16) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::MakeText,
+                    MakeTextRequest { text, length }.encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Clears the content of a text.
+    fn clear_text(&self, station: u8, text: u16) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::ClearText,
+                    ClearTextRequest { text }.encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+}
+
+async fn read_data_block_chunks<C: SBusClient + ?Sized>(client: &C, station: u8, command_id: CommandId, block: u16, offset: u16, length: usize) -> Result<Vec<i32>, SBusError> {
+    validate_input(offset, length, u16::MAX)?;
+    let mut values = Vec::with_capacity(length);
+    while values.len() < length {
+        let chunk_length = usize::min(length - values.len(), DATA_BLOCK_MAX_REQUEST_LEN as usize);
+        let res_body = client
             .send_request(
                 station,
-                CommandId::WriteTimers,
-                WriteTimersRequest {
-                    address,
-                    values: values.into(),
+                command_id,
+                ReadDataBlockRequest {
+                    block,
+                    offset: offset + values.len() as u16,
+                    length: chunk_length as u8,
                 }
                 .encode_to_bytes()?,
-                TelegramAttribute::Acknowledge,
+                TelegramAttribute::Response,
             )
             .await?;
-        let res = Acknowledge::decode_from_bytes(&res_body)?;
-        Ok(res == Acknowledge::Ack)
+        let res = ReadDataBlockResponse::decode_from_bytes(&res_body)?;
+        if res.values.len() != chunk_length {
+            return Err(SBusError::InvalidResponse("Unexpected number of values"));
+        }
+        values.extend_from_slice(&res.values);
     }
+    Ok(values)
+}
 
-    async fn send_request(&self, station: u8, command_id: CommandId, body: Vec<u8>, response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
-        let sequence_number = self.sequence_number.fetch_add(1, Ordering::Relaxed);
+/// Splits a range into chunks of at most `max_length` elements, as pairs of address and length.
+fn split_range(address: u16, length: usize, max_length: u16) -> Vec<(u16, usize)> {
+    (0..length)
+        .step_by(max_length as usize)
+        .map(|offset| (address + offset as u16, usize::min(max_length as usize, length - offset)))
+        .collect()
+}
 
-        let req = Request {
-            station,
-            command_id,
-            body: body.into(),
-        };
+/// Runs the requests for the chunks in order with at most `pipeline` of them at once and returns their results in order.
+/// Stops at the first failed request and reports its chunk.
+async fn join_chunks<T, F>(chunks: &[(u16, usize)], requests: impl Iterator<Item = F>, pipeline: usize) -> Result<Vec<T>, SBusError>
+where
+    F: Future<Output = Result<T, SBusError>>,
+{
+    let mut requests = requests.enumerate();
+    let mut running: Vec<(usize, Pin<Box<F>>)> = Vec::new();
+    let mut results: Vec<Option<T>> = Vec::new();
 
-        let req_msg = Message {
-            sequence_number,
-            telegram_attribute: TelegramAttribute::Request,
-            body: req.encode_to_bytes()?,
-        };
+    std::future::poll_fn(|cx| loop {
+        while running.len() < pipeline.max(1) {
+            let Some((index, request)) = requests.next() else {
+                break;
+            };
+            results.push(None);
+            running.push((index, Box::pin(request)));
+        }
+        if running.is_empty() {
+            return Poll::Ready(Ok(()));
+        }
 
-        let req_bytes = req_msg.encode_to_bytes()?;
+        let mut completed = false;
+        let mut i = 0;
+        while i < running.len() {
+            let (index, request) = &mut running[i];
+            match request.as_mut().poll(cx) {
+                Poll::Ready(Ok(result)) => {
+                    results[*index] = Some(result);
+                    running.remove(i);
+                    completed = true;
+                }
+                Poll::Ready(Err(error)) => {
+                    return Poll::Ready(Err(SBusError::Chunk {
+                        index: *index,
+                        address: chunks[*index].0,
+                        error: Box::new(error),
+                    }))
+                }
+                Poll::Pending => i += 1,
+            }
+        }
+
+        // Start the next requests if some completed, otherwise wait to be woken up
+        if !completed {
+            return Poll::Pending;
+        }
+    })
+    .await?;
+
+    Ok(results.into_iter().flatten().collect())
+}
+
+/// Returns the body of a reply if it is of the expected type.
+/// A station that refuses a request for data replies with an acknowledge instead, which is returned as [`SBusError::Nak`].
+pub(crate) fn response_body(telegram_attribute: TelegramAttribute, body: Vec<u8>, response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
+    if telegram_attribute == response_type {
+        return Ok(body);
+    }
+    if telegram_attribute == TelegramAttribute::Acknowledge {
+        acknowledge_result(&body)?;
+    }
+    Err(SBusError::InvalidResponse("Telegram attribute mismatch"))
+}
+
+/// Decodes an acknowledge response and turns anything but [`Acknowledge::Ack`] into [`SBusError::Nak`].
+pub(crate) fn acknowledge_result(res_body: &[u8]) -> Result<(), SBusError> {
+    match Acknowledge::decode_from_bytes(res_body)? {
+        Acknowledge::Ack => Ok(()),
+        ack => Err(SBusError::Nak(ack)),
+    }
+}
 
-        let (sender, receiver) = oneshot::channel::<ResponseResult>();
+pub(crate) fn validate_input(address: u16, length: usize, max_length: u16) -> Result<(), SBusError> {
+    if length == 0 || length > max_length as usize {
+        return Err(SBusError::ArgumentsOutOfRange("Length exceeds maximum allowed length"));
+    }
+    u16::checked_add(address, (length - 1) as u16).ok_or(SBusError::ArgumentsOutOfRange("Address + length exceeds device address space"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpu::Cpu,
+        request::Request,
+        server::{dispatch, Reply},
+        simulator::{SBusSimulator, MEDIA_SIZE},
+    };
+
+    struct MockClient;
 
-        {
-            let mut map = self.response_map.lock().await;
-            map.insert(sequence_number, sender);
+    impl SBusClient for MockClient {
+        async fn send_request(&self, station: u8, command_id: CommandId, _body: Vec<u8>, response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
+            match (station, command_id, response_type) {
+                (1, CommandId::ReadDisplayRegister, TelegramAttribute::Response) => Ok(ReadDisplayRegisterResponse { register: 42 }.encode_to_bytes()?),
+                (1, _, TelegramAttribute::Acknowledge) => Ok(Acknowledge::Nak.encode_to_bytes()?),
+                _ => Err(SBusError::Timeout),
+            }
         }
+    }
 
-        self.socket.send(&req_bytes).await?;
+    /// A data block of 200 elements, initialized with the element offsets as values.
+    struct DataBlockMock(std::sync::Mutex<Vec<i32>>);
 
-        let res_msg = match receiver.await {
-            Ok(Ok(msg)) => msg,
-            Ok(Err(error)) => return Err(error),
-            Err(_err) => return Err(SBusError::Internal("Too many concurrent requests")),
-        };
+    impl DataBlockMock {
+        fn new() -> Self {
+            Self(std::sync::Mutex::new((0..200).collect()))
+        }
+    }
 
-        if res_msg.telegram_attribute != response_type {
-            return Err(SBusError::InvalidResponse("Telegram attribute mismatch"));
+    impl SBusClient for DataBlockMock {
+        async fn send_request(&self, _station: u8, command_id: CommandId, body: Vec<u8>, _response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
+            let mut block = self.0.lock().unwrap();
+            match command_id {
+                CommandId::ReadDataBlock => {
+                    let req = ReadDataBlockRequest::decode_from_bytes(&body)?;
+                    assert!(req.length as u16 <= DATA_BLOCK_MAX_REQUEST_LEN);
+                    let values = &block[req.offset as usize..req.offset as usize + req.length as usize];
+                    Ok(ReadDataBlockResponse { values: values.into() }.encode_to_bytes()?)
+                }
+                CommandId::WriteDataBlock => {
+                    let req = WriteDataBlockRequest::decode_from_bytes(&body)?;
+                    assert!(req.values.len() <= DATA_BLOCK_MAX_REQUEST_LEN as usize);
+                    block[req.offset as usize..req.offset as usize + req.values.len()].copy_from_slice(&req.values);
+                    Ok(Acknowledge::Ack.encode_to_bytes()?)
+                }
+                _ => Err(SBusError::Timeout),
+            }
         }
+    }
 
-        Ok(res_msg.body)
+    /// A text of 300 spaces, which counts the telegrams it receives.
+    struct TextMock {
+        text: std::sync::Mutex<Vec<char>>,
+        telegrams: std::sync::atomic::AtomicUsize,
     }
 
-    async fn receive_response(socket: Arc<UdpSocket>, response_map: ResponseMap) -> Result<(), SBusError> {
-        let mut read_buffer = [0; 256];
-        loop {
-            async fn recv(socket: &UdpSocket, read_buffer: &mut [u8]) -> Result<Message, SBusError> {
-                let byte_length = socket.recv(read_buffer).await?;
-                Ok(Message::decode_from_bytes(&read_buffer[0..byte_length])?)
+    impl TextMock {
+        fn new() -> Self {
+            Self {
+                text: std::sync::Mutex::new(vec![' '; 300]),
+                telegrams: Default::default(),
             }
+        }
 
-            let msg = match recv(&socket, &mut read_buffer).await {
-                Ok(msg) => msg,
-                Err(error) => {
-                    let mut response_map = response_map.lock().await;
-                    for (_, sender) in response_map.drain() {
-                        _ = sender.send(Err(error.clone()));
-                    }
-                    return Err(error);
+        fn telegrams(&self) -> usize {
+            self.telegrams.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    impl SBusClient for TextMock {
+        async fn send_request(&self, _station: u8, command_id: CommandId, body: Vec<u8>, _response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
+            self.telegrams.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let mut text = self.text.lock().unwrap();
+            match command_id {
+                CommandId::ReadText => {
+                    let req = ReadTextRequest::decode_from_bytes(&body)?;
+                    assert!(req.length as u16 <= TEXT_MAX_REQUEST_LEN);
+                    let value: String = text[req.offset as usize..req.offset as usize + req.length as usize].iter().collect();
+                    Ok(ReadTextResponse { value: value.into() }.encode_to_bytes()?)
+                }
+                CommandId::WriteText => {
+                    let req = WriteTextRequest::decode_from_bytes(&body)?;
+                    let chars: Vec<char> = req.value.chars().collect();
+                    assert!(chars.len() <= TEXT_MAX_REQUEST_LEN as usize);
+                    text[req.offset as usize..req.offset as usize + chars.len()].copy_from_slice(&chars);
+                    Ok(Acknowledge::Ack.encode_to_bytes()?)
                 }
+                _ => Err(SBusError::Timeout),
+            }
+        }
+    }
+
+    /// Records the command of every request and acknowledges it.
+    struct CpuMock(std::sync::Mutex<Vec<CommandId>>);
+
+    impl CpuMock {
+        fn new() -> Self {
+            Self(std::sync::Mutex::new(Vec::new()))
+        }
+
+        fn commands(&self) -> Vec<CommandId> {
+            std::mem::take(&mut self.0.lock().unwrap())
+        }
+    }
+
+    impl SBusClient for CpuMock {
+        async fn send_request(&self, _station: u8, command_id: CommandId, _body: Vec<u8>, response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
+            self.0.lock().unwrap().push(command_id);
+            assert_eq!(response_type, TelegramAttribute::Acknowledge);
+            Ok(Acknowledge::Ack.encode_to_bytes()?)
+        }
+    }
+
+    #[tokio::test]
+    async fn run_stop_and_restart_cpus() {
+        let client = CpuMock::new();
+        client.run_cpu(0, CpuSelection::All).await.unwrap();
+        client.run_cpu(0, Cpu::Cpu0).await.unwrap();
+        client.stop_cpu(0, Cpu::Own).await.unwrap();
+        client.stop_cpu(0, Cpu::Cpu6).await.unwrap();
+        client.restart_cpu_cold(0, Cpu::Cpu1).await.unwrap();
+        client.restart_cpu_cold(0, CpuSelection::All).await.unwrap();
+        client.restart_cpu_warm(0, Cpu::Own).await.unwrap();
+        client.restart_cpu_warm(0, Cpu::Cpu3).await.unwrap();
+        assert_eq!(
+            client.commands(),
+            vec![
+                CommandId::RunProcedureAllCPUS,
+                CommandId::RunProcedureCPU0,
+                CommandId::StopProcedureOwnCPU,
+                CommandId::StopProcedureCPU6,
+                CommandId::RestartColdCPU1,
+                CommandId::RestartColdAllCPUS,
+                CommandId::RestartWarmOwnCPU,
+                CommandId::RestartWarmCPU3,
+            ]
+        );
+
+        // CPU 0 has no individual restart command, so nothing is sent
+        assert!(matches!(client.restart_cpu_cold(0, Cpu::Cpu0).await, Err(SBusError::ArgumentsOutOfRange(_))));
+        assert!(matches!(client.restart_cpu_warm(0, Cpu::Cpu0).await, Err(SBusError::ArgumentsOutOfRange(_))));
+        assert_eq!(client.commands(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn trigger_interrupts() {
+        let client = CpuMock::new();
+        for xob in 17..=19 {
+            client.trigger_interrupt(0, Interrupt::try_from(xob).unwrap()).await.unwrap();
+        }
+        let commands: Vec<u8> = client.commands().into_iter().map(u8::from).collect();
+        assert_eq!(commands, vec![0x82, 0x83, 0x84]);
+
+        for xob in [0, 16, 20, u8::MAX] {
+            assert!(matches!(Interrupt::try_from(xob), Err(SBusError::ArgumentsOutOfRange(_))));
+        }
+
+        assert!(matches!(MockClient.trigger_interrupt(1, Interrupt::Xob17).await, Err(SBusError::Nak(Acknowledge::Nak))));
+    }
+
+    /// Serves requests from a simulator and records the command and body of every request.
+    /// Refuses the request with the index of `refused_request`, counted from the first request.
+    struct SimulatorMock {
+        simulator: SBusSimulator,
+        requests: std::sync::Mutex<Vec<(CommandId, Vec<u8>)>>,
+        refused_request: Option<usize>,
+    }
+
+    impl SimulatorMock {
+        /// Returns a simulator with every flag and output set and every register and timer set to 1.
+        fn new() -> Self {
+            let simulator = SBusSimulator::new(0);
+            simulator.set_flags(0, &vec![true; MEDIA_SIZE]);
+            simulator.set_outputs(0, &vec![true; MEDIA_SIZE]);
+            simulator.set_registers(0, &vec![1; MEDIA_SIZE]);
+            simulator.set_timers(0, &vec![1; MEDIA_SIZE]);
+            Self {
+                simulator,
+                requests: Default::default(),
+                refused_request: None,
+            }
+        }
+
+        fn requests(&self) -> Vec<(CommandId, Vec<u8>)> {
+            std::mem::take(&mut self.requests.lock().unwrap())
+        }
+    }
+
+    impl SBusClient for SimulatorMock {
+        async fn send_request(&self, station: u8, command_id: CommandId, body: Vec<u8>, _response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
+            let index = {
+                let mut requests = self.requests.lock().unwrap();
+                requests.push((command_id, body.clone()));
+                requests.len() - 1
+            };
+            if self.refused_request == Some(index) {
+                return Ok(Acknowledge::Nak.encode_to_bytes()?);
+            }
+            let req = Request {
+                station,
+                command_id,
+                body: body.into(),
             };
+            match dispatch(&self.simulator, &req).await {
+                Some(Reply::Response(body)) => Ok(body),
+                Some(Reply::Acknowledge(ack)) => Ok(ack.encode_to_bytes()?),
+                None => Err(SBusError::Timeout),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn clear_all_fort() {
+        let client = SimulatorMock::new();
+        client.simulator.set_counters(0, &[1]);
+        client.clear_all_fort(0).await.unwrap();
+        assert_eq!(client.requests(), vec![(CommandId::ClearAllFORT, vec![])]);
+        assert_eq!(u8::from(CommandId::ClearAllFORT), 0x5A);
+        assert_eq!(client.simulator.flags(0, MEDIA_SIZE), vec![false; MEDIA_SIZE]);
+        assert_eq!(client.simulator.outputs(0, MEDIA_SIZE), vec![false; MEDIA_SIZE]);
+        assert_eq!(client.simulator.registers(0, MEDIA_SIZE), vec![0; MEDIA_SIZE]);
+        assert_eq!(client.simulator.timers(0, MEDIA_SIZE), vec![0; MEDIA_SIZE]);
+        // Counters are not part of FORT
+        assert_eq!(client.simulator.counters(0, 1), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn clear_flags() {
+        let client = SimulatorMock::new();
+        client.clear_flags(0).await.unwrap();
+        assert_eq!(client.requests(), vec![(CommandId::ClearFlags, vec![])]);
+        assert_eq!(u8::from(CommandId::ClearFlags), 0x5B);
+        assert_eq!(client.simulator.flags(0, MEDIA_SIZE), vec![false; MEDIA_SIZE]);
+        assert_eq!(client.simulator.outputs(0, 1), vec![true]);
+        assert_eq!(client.simulator.registers(0, 1), vec![1]);
+        assert_eq!(client.simulator.timers(0, 1), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn clear_outputs() {
+        let client = SimulatorMock::new();
+        client.clear_outputs(0).await.unwrap();
+        assert_eq!(client.requests(), vec![(CommandId::ClearOutputs, vec![])]);
+        assert_eq!(u8::from(CommandId::ClearOutputs), 0x5C);
+        assert_eq!(client.simulator.outputs(0, MEDIA_SIZE), vec![false; MEDIA_SIZE]);
+        assert_eq!(client.simulator.flags(0, 1), vec![true]);
+        assert_eq!(client.simulator.registers(0, 1), vec![1]);
+        assert_eq!(client.simulator.timers(0, 1), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn clear_timers() {
+        let client = SimulatorMock::new();
+        client.clear_timers(0).await.unwrap();
+        assert_eq!(client.requests(), vec![(CommandId::ClearTimers, vec![])]);
+        assert_eq!(u8::from(CommandId::ClearTimers), 0x5E);
+        assert_eq!(client.simulator.timers(0, MEDIA_SIZE), vec![0; MEDIA_SIZE]);
+        assert_eq!(client.simulator.flags(0, 1), vec![true]);
+        assert_eq!(client.simulator.outputs(0, 1), vec![true]);
+        assert_eq!(client.simulator.registers(0, 1), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn write_range_across_chunks() {
+        let client = SimulatorMock::new();
+        let values = MediaValues::Integers((0..40).collect());
+        client.write_media_range(0, Media::Registers, 20, &values, 2).await.unwrap();
 
-            let sender = response_map.lock().await.remove(&msg.sequence_number);
-            match sender {
-                None => return Err(SBusError::InvalidResponse("The server sent an unexpected response")),
-                Some(sender) => _ = sender.send(Ok(msg)),
+        let requests: Vec<WriteRegistersRequest> = client
+            .requests()
+            .into_iter()
+            .map(|(command_id, body)| {
+                assert_eq!(command_id, CommandId::WriteRegisters);
+                WriteRegistersRequest::decode_from_bytes(&body).unwrap()
+            })
+            .collect();
+        assert_eq!(requests.iter().map(|req| (req.address, req.values.len())).collect::<Vec<_>>(), vec![(20, 32), (52, 8)]);
+        assert_eq!(client.simulator.registers(20, 40), (0..40).collect::<Vec<i32>>());
+        assert_eq!(client.simulator.registers(19, 1), vec![1]);
+        assert_eq!(client.simulator.registers(60, 1), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn write_range_with_failed_chunk() {
+        let values = MediaValues::Integers(vec![0; 100]);
+        for pipeline in [1, 4] {
+            let client = SimulatorMock {
+                refused_request: Some(1),
+                ..SimulatorMock::new()
+            };
+            let result = client.write_media_range(0, Media::Registers, 10, &values, pipeline).await;
+            let Err(SBusError::Chunk { index, address, error }) = result else {
+                panic!("Unexpected result {result:?}");
+            };
+            assert_eq!((index, address), (1, 42));
+            assert!(matches!(*error, SBusError::Nak(Acknowledge::Nak)));
+            // The chunk before the refused one is written
+            assert_eq!(client.simulator.registers(10, 32), vec![0; 32]);
+            assert_eq!(client.simulator.registers(42, 32), vec![1; 32]);
+        }
+    }
+
+    /// Answers register reads with the addresses as values, up to address 1000, and refuses all other requests.
+    struct RegistersMock;
+
+    impl SBusClient for RegistersMock {
+        async fn send_request(&self, _station: u8, command_id: CommandId, body: Vec<u8>, _response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
+            match command_id {
+                CommandId::ReadRegisters => {
+                    let req = ReadRegistersRequest::decode_from_bytes(&body)?;
+                    if req.address + req.length as u16 > 1000 {
+                        return Err(SBusError::Timeout);
+                    }
+                    let values: Vec<i32> = (req.address..req.address + req.length as u16).map(i32::from).collect();
+                    Ok(ReadRegistersResponse { values: values.into() }.encode_to_bytes()?)
+                }
+                _ => Ok(Acknowledge::NakPassword.encode_to_bytes()?),
             }
         }
     }
-}
 
-impl Drop for SBusUDPClient {
-    fn drop(&mut self) {
-        self.abort_handle.abort();
+    #[tokio::test]
+    async fn read_write_multimedia_items() {
+        let request = MultimediaRequest::new().read(Media::Registers, 10, 2).write_flags(0, &[true]);
+        let results = RegistersMock.read_write_multimedia(0, &request).await.unwrap();
+        assert_eq!(
+            results,
+            vec![MultimediaResult::Values(MediaValues::Integers(vec![10, 11])), MultimediaResult::Refused(Acknowledge::NakPassword)]
+        );
+
+        let request = MultimediaRequest::new().write_flags(0, &[true; 129]);
+        assert!(matches!(RegistersMock.read_write_multimedia(0, &request).await, Err(SBusError::ArgumentsOutOfRange(_))));
+
+        // Only refusals are returned as results
+        let request = MultimediaRequest::new().read(Media::Registers, 10, 2);
+        assert!(matches!(MockClient.read_write_multimedia(2, &request).await, Err(SBusError::Timeout)));
     }
-}
 
-fn validate_input(address: u16, length: usize, max_length: u16) -> Result<(), SBusError> {
-    if length == 0 || length > max_length as usize {
-        return Err(SBusError::ArgumentsOutOfRange("Length exceeds maximum allowed length"));
+    #[tokio::test]
+    async fn read_range_in_chunks() {
+        let values = RegistersMock.read_registers_range(0, 10, 70).await.unwrap();
+        assert_eq!(values, (10..80).collect::<Vec<i32>>());
+        let values = RegistersMock.read_media_range(0, Media::Registers, 0, 1000, 4).await.unwrap();
+        assert_eq!(values, MediaValues::Integers((0..1000).collect()));
+
+        let result = RegistersMock.read_media_range(0, Media::Registers, 900, 200, 4).await;
+        assert!(matches!(result, Err(SBusError::Chunk { index: 3, address: 996, .. })));
+    }
+
+    #[tokio::test]
+    async fn read_planned_items() {
+        let plan = ReadPlan::new([(Media::Registers, 5), (Media::Registers, 900), (Media::Registers, 7), (Media::Registers, 60)], 10);
+        let table = RegistersMock.read_planned(0, &plan, 2).await.unwrap();
+        assert_eq!(table.len(), 4);
+        assert_eq!(table[&(Media::Registers, 7)], MediaValue::Integer(7));
+        assert_eq!(table[&(Media::Registers, 900)], MediaValue::Integer(900));
+
+        let plan = ReadPlan::new([(Media::Registers, 5), (Media::Registers, 1200)], 10);
+        let result = RegistersMock.read_planned(0, &plan, 1).await;
+        assert!(matches!(result, Err(SBusError::Chunk { index: 1, address: 1200, .. })));
+    }
+
+    #[tokio::test]
+    async fn read_data_block_in_chunks() {
+        let client = DataBlockMock::new();
+        let values = client.read_data_block(0, 4000, 10, 70).await.unwrap();
+        assert_eq!(values, (10..80).collect::<Vec<i32>>());
+    }
+
+    #[tokio::test]
+    async fn write_data_block_in_chunks() {
+        let client = DataBlockMock::new();
+        client.write_data_block(0, 4000, 5, &[-1; 70]).await.unwrap();
+        let values = client.read_data_block(0, 4000, 0, 80).await.unwrap();
+        assert_eq!(values[..5], [0, 1, 2, 3, 4]);
+        assert_eq!(values[5..75], [-1; 70]);
+        assert_eq!(values[75..], [75, 76, 77, 78, 79]);
+    }
+
+    #[tokio::test]
+    async fn write_and_read_text_in_chunks() {
+        let client = TextMock::new();
+        let value = "Température ".repeat(20);
+        client.write_text(0, 10, 10, &value).await.unwrap();
+        assert_eq!(client.telegrams(), 2);
+        assert_eq!(client.read_text(0, 10, 10, 240).await.unwrap(), value);
+        assert_eq!(client.telegrams(), 4);
+        assert_eq!(client.read_text(0, 10, 8, 3).await.unwrap(), "  T");
+    }
+
+    #[tokio::test]
+    async fn reject_text_outside_latin1() {
+        let client = TextMock::new();
+        let value = format!("{}€", "a".repeat(200));
+        assert!(matches!(client.write_text(0, 10, 0, &value).await, Err(SBusError::ArgumentsOutOfRange(_))));
+        // Nothing is written, not even the chunk before the invalid character
+        assert_eq!(client.telegrams(), 0);
+        assert_eq!(client.read_text(0, 10, 0, 1).await.unwrap(), " ");
+    }
+
+    #[tokio::test]
+    async fn mock_transport() {
+        let client = MockClient;
+
+        assert_eq!(client.read_display_register(1).await.unwrap(), 42);
+        assert!(matches!(client.write_flags(1, 0, &[true]).await, Err(SBusError::Nak(Acknowledge::Nak))));
+        assert!(matches!(client.read_display_register(2).await, Err(SBusError::Timeout)));
+        assert!(matches!(client.write_flags(1, 0, &[]).await, Err(SBusError::ArgumentsOutOfRange(_))));
+        assert!(matches!(client.write_user_eeprom_registers(1, u16::MAX, &[1, 2]).await, Err(SBusError::ArgumentsOutOfRange(_))));
     }
-    u16::checked_add(address, (length - 1) as u16).ok_or(SBusError::ArgumentsOutOfRange("Address + length exceeds device address space"))?;
-    Ok(())
 }
@@ -0,0 +1,19 @@
+use crate::encoding::*;
+
+#[derive(PartialEq, Debug)]
+pub struct ClearDataBlockRequest {
+    pub block: u16,
+}
+
+impl Encodable for ClearDataBlockRequest {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_u16(self.block);
+        Ok(())
+    }
+}
+
+impl Decodable<Self> for ClearDataBlockRequest {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        Ok(Self { block: decoder.read_u16()? })
+    }
+}
@@ -0,0 +1,19 @@
+use crate::encoding::*;
+
+#[derive(PartialEq, Debug)]
+pub struct ClearTextRequest {
+    pub text: u16,
+}
+
+impl Encodable for ClearTextRequest {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_u16(self.text);
+        Ok(())
+    }
+}
+
+impl Decodable<Self> for ClearTextRequest {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        Ok(Self { text: decoder.read_u16()? })
+    }
+}
@@ -0,0 +1,24 @@
+use crate::encoding::*;
+
+#[derive(PartialEq, Debug)]
+pub struct MakeDataBlockRequest {
+    pub block: u16,
+    pub length: u16,
+}
+
+impl Encodable for MakeDataBlockRequest {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_u16(self.block);
+        encoder.write_u16(self.length);
+        Ok(())
+    }
+}
+
+impl Decodable<Self> for MakeDataBlockRequest {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        Ok(Self {
+            block: decoder.read_u16()?,
+            length: decoder.read_u16()?,
+        })
+    }
+}
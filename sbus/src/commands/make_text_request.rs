@@ -0,0 +1,24 @@
+use crate::encoding::*;
+
+#[derive(PartialEq, Debug)]
+pub struct MakeTextRequest {
+    pub text: u16,
+    pub length: u16,
+}
+
+impl Encodable for MakeTextRequest {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_u16(self.text);
+        encoder.write_u16(self.length);
+        Ok(())
+    }
+}
+
+impl Decodable<Self> for MakeTextRequest {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        Ok(Self {
+            text: decoder.read_u16()?,
+            length: decoder.read_u16()?,
+        })
+    }
+}
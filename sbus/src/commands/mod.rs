@@ -1,9 +1,18 @@
+mod clear_data_block_request;
+mod clear_text_request;
+mod make_data_block_request;
+mod make_text_request;
+mod read_bytes_request;
+mod read_bytes_response;
 mod read_counters_request;
 mod read_counters_response;
+mod read_data_block_request;
+mod read_data_block_response;
 mod read_display_register_response;
 mod read_firmware_version_response;
 mod read_flags_request;
 mod read_flags_response;
+mod read_halt_failure_register_response;
 mod read_inputs_request;
 mod read_inputs_response;
 mod read_outputs_request;
@@ -12,21 +21,41 @@ mod read_real_time_clock_response;
 mod read_registers_request;
 mod read_registers_response;
 mod read_sbus_station_number_response;
+mod read_text_request;
+mod read_text_response;
 mod read_timers_request;
 mod read_timers_response;
+mod read_user_eeprom_registers_request;
+mod read_user_eeprom_registers_response;
+mod read_user_memory_request;
+mod read_user_memory_response;
+mod write_bytes_request;
 mod write_counters_request;
+mod write_data_block_request;
 mod write_flags_request;
 mod write_outputs_request;
 mod write_real_time_clock_request;
 mod write_registers_request;
+mod write_text_request;
 mod write_timers_request;
+mod write_user_eeprom_registers_request;
+mod write_user_memory_request;
 
+pub use clear_data_block_request::*;
+pub use clear_text_request::*;
+pub use make_data_block_request::*;
+pub use make_text_request::*;
+pub use read_bytes_request::*;
+pub use read_bytes_response::*;
 pub use read_counters_request::*;
 pub use read_counters_response::*;
+pub use read_data_block_request::*;
+pub use read_data_block_response::*;
 pub use read_display_register_response::*;
 pub use read_firmware_version_response::*;
 pub use read_flags_request::*;
 pub use read_flags_response::*;
+pub use read_halt_failure_register_response::*;
 pub use read_inputs_request::*;
 pub use read_inputs_response::*;
 pub use read_outputs_request::*;
@@ -35,11 +64,22 @@ pub use read_real_time_clock_response::*;
 pub use read_registers_request::*;
 pub use read_registers_response::*;
 pub use read_sbus_station_number_response::*;
+pub use read_text_request::*;
+pub use read_text_response::*;
 pub use read_timers_request::*;
 pub use read_timers_response::*;
+pub use read_user_eeprom_registers_request::*;
+pub use read_user_eeprom_registers_response::*;
+pub use read_user_memory_request::*;
+pub use read_user_memory_response::*;
+pub use write_bytes_request::*;
 pub use write_counters_request::*;
+pub use write_data_block_request::*;
 pub use write_flags_request::*;
 pub use write_outputs_request::*;
 pub use write_real_time_clock_request::*;
 pub use write_registers_request::*;
+pub use write_text_request::*;
 pub use write_timers_request::*;
+pub use write_user_eeprom_registers_request::*;
+pub use write_user_memory_request::*;
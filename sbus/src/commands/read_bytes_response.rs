@@ -0,0 +1,23 @@
+use std::borrow::Cow;
+
+use crate::encoding::*;
+
+#[derive(PartialEq, Debug)]
+pub struct ReadBytesResponse<'a> {
+    pub bytes: Cow<'a, [u8]>,
+}
+
+impl<'a> Encodable for ReadBytesResponse<'a> {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_bytes(&self.bytes);
+        Ok(())
+    }
+}
+
+impl<'a> Decodable<Self> for ReadBytesResponse<'a> {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        Ok(Self {
+            bytes: decoder.read_bytes(decoder.remaining())?.into(),
+        })
+    }
+}
@@ -0,0 +1,31 @@
+use crate::encoding::*;
+
+/// Also used for [`crate::CommandId::ReadDBX`], which shares the layout.
+#[derive(PartialEq, Debug)]
+pub struct ReadDataBlockRequest {
+    pub block: u16,
+    pub offset: u16,
+    pub length: u8,
+}
+
+impl Encodable for ReadDataBlockRequest {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_u8(self.length.checked_sub(1).ok_or(EncodeError::Overflow)?);
+        encoder.write_u16(self.block);
+        encoder.write_u16(self.offset);
+        Ok(())
+    }
+}
+
+impl Decodable<Self> for ReadDataBlockRequest {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        Ok(Self {
+            length: decoder
+                .read_u8()?
+                .checked_add(1)
+                .ok_or(DecodeError::InvalidData("Invalid length"))?,
+            block: decoder.read_u16()?,
+            offset: decoder.read_u16()?,
+        })
+    }
+}
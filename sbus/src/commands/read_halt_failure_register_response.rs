@@ -0,0 +1,21 @@
+use crate::encoding::*;
+
+#[derive(PartialEq, Debug)]
+pub struct ReadHaltFailureRegisterResponse {
+    pub register: u32,
+}
+
+impl Encodable for ReadHaltFailureRegisterResponse {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_u32(self.register);
+        Ok(())
+    }
+}
+
+impl Decodable<Self> for ReadHaltFailureRegisterResponse {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        Ok(Self {
+            register: decoder.read_u32()?,
+        })
+    }
+}
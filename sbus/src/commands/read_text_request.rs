@@ -0,0 +1,30 @@
+use crate::encoding::*;
+
+#[derive(PartialEq, Debug)]
+pub struct ReadTextRequest {
+    pub text: u16,
+    pub offset: u16,
+    pub length: u8,
+}
+
+impl Encodable for ReadTextRequest {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_u8(self.length.checked_sub(1).ok_or(EncodeError::Overflow)?);
+        encoder.write_u16(self.text);
+        encoder.write_u16(self.offset);
+        Ok(())
+    }
+}
+
+impl Decodable<Self> for ReadTextRequest {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        Ok(Self {
+            length: decoder
+                .read_u8()?
+                .checked_add(1)
+                .ok_or(DecodeError::InvalidData("Invalid length"))?,
+            text: decoder.read_u16()?,
+            offset: decoder.read_u16()?,
+        })
+    }
+}
@@ -0,0 +1,22 @@
+use std::borrow::Cow;
+
+use crate::encoding::*;
+
+#[derive(PartialEq, Debug)]
+pub struct ReadTextResponse<'a> {
+    pub value: Cow<'a, str>,
+}
+
+impl<'a> Encodable for ReadTextResponse<'a> {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_latin1(&self.value)
+    }
+}
+
+impl<'a> Decodable<Self> for ReadTextResponse<'a> {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        Ok(Self {
+            value: decoder.read_latin1(decoder.remaining())?.into(),
+        })
+    }
+}
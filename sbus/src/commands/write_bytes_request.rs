@@ -0,0 +1,31 @@
+use std::borrow::Cow;
+
+use crate::encoding::*;
+
+#[derive(PartialEq, Debug)]
+pub struct WriteBytesRequest<'a> {
+    pub address: u32,
+    pub bytes: Cow<'a, [u8]>,
+}
+
+impl<'a> Encodable for WriteBytesRequest<'a> {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_u8((self.bytes.len() + 3).try_into()?);
+        encoder.write_u32(self.address);
+        encoder.write_bytes(&self.bytes);
+        Ok(())
+    }
+}
+
+impl<'a> Decodable<Self> for WriteBytesRequest<'a> {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        let length = decoder
+            .read_u8()?
+            .checked_sub(3)
+            .ok_or(DecodeError::InvalidData("Invalid length"))?;
+        Ok(Self {
+            address: decoder.read_u32()?,
+            bytes: decoder.read_bytes(length as usize)?.into(),
+        })
+    }
+}
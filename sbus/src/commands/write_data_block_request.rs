@@ -0,0 +1,47 @@
+use std::borrow::Cow;
+
+use crate::encoding::*;
+
+#[derive(PartialEq, Debug)]
+pub struct WriteDataBlockRequest<'a> {
+    pub block: u16,
+    pub offset: u16,
+    pub values: Cow<'a, [i32]>,
+}
+
+impl<'a> Encodable for WriteDataBlockRequest<'a> {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_u8((self.values.len() * 4 + 3).try_into()?);
+        encoder.write_u16(self.block);
+        encoder.write_u16(self.offset);
+        encoder.reserve(self.values.len());
+        for value in self.values.iter() {
+            encoder.write_i32(*value);
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Decodable<Self> for WriteDataBlockRequest<'a> {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        let length = decoder
+            .read_u8()?
+            .checked_sub(3)
+            .ok_or(DecodeError::InvalidData("Invalid length"))?;
+        if length % 4 != 0 {
+            return Err(DecodeError::InvalidData("Invalid length"));
+        }
+        let length = length / 4;
+        let block = decoder.read_u16()?;
+        let offset = decoder.read_u16()?;
+        let mut values = Vec::with_capacity(length as usize);
+        for _ in 0..length {
+            values.push(decoder.read_i32()?);
+        }
+        Ok(Self {
+            block,
+            offset,
+            values: values.into(),
+        })
+    }
+}
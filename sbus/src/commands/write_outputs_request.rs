@@ -30,7 +30,7 @@ impl<'a> Decodable<Self> for WriteOutputsRequest<'a> {
             .read_u8()?
             .checked_add(1)
             .ok_or(DecodeError::InvalidData("Invalid length"))?;
-        let mut values = decoder.read_bools(byte_length as usize)?;
+        let mut values = decoder.read_bools(byte_length as usize * 8)?;
         values.truncate(length as usize);
 
         Ok(Self {
@@ -0,0 +1,34 @@
+use std::borrow::Cow;
+
+use crate::encoding::*;
+
+#[derive(PartialEq, Debug)]
+pub struct WriteTextRequest<'a> {
+    pub text: u16,
+    pub offset: u16,
+    pub value: Cow<'a, str>,
+}
+
+impl<'a> Encodable for WriteTextRequest<'a> {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        encoder.write_u8((self.value.chars().count() + 3).try_into()?);
+        encoder.write_u16(self.text);
+        encoder.write_u16(self.offset);
+        encoder.write_latin1(&self.value)?;
+        Ok(())
+    }
+}
+
+impl<'a> Decodable<Self> for WriteTextRequest<'a> {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        let length = decoder
+            .read_u8()?
+            .checked_sub(3)
+            .ok_or(DecodeError::InvalidData("Invalid length"))?;
+        Ok(Self {
+            text: decoder.read_u16()?,
+            offset: decoder.read_u16()?,
+            value: decoder.read_latin1(length as usize)?.into(),
+        })
+    }
+}
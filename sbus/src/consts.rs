@@ -1,6 +1,12 @@
+/// The station address of a request to all stations, which is never replied to.
+pub const BROADCAST_STATION: u8 = 255;
 pub const COUNTERS_MAX_REQUEST_LEN: u16 = 32;
+pub const DATA_BLOCK_MAX_REQUEST_LEN: u16 = 32;
 pub const FLAGS_MAX_REQUEST_LEN: u16 = 128;
 pub const INPUTS_MAX_REQUEST_LEN: u16 = 128;
+pub const MEMORY_MAX_REQUEST_LEN: u16 = 128;
 pub const OUTPUTS_MAX_REQUEST_LEN: u16 = 128;
 pub const REGISTERS_MAX_REQUEST_LEN: u16 = 32;
+pub const TEXT_MAX_REQUEST_LEN: u16 = 128;
 pub const TIMERS_MAX_REQUEST_LEN: u16 = 32;
+pub const USER_EEPROM_REGISTERS_MAX_REQUEST_LEN: u16 = 32;
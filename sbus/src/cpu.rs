@@ -0,0 +1,155 @@
+use crate::command_id::CommandId;
+
+/// Selects a single CPU of a PCD.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cpu {
+    /// The CPU the S-Bus connection is handled by.
+    Own,
+    Cpu0,
+    Cpu1,
+    Cpu2,
+    Cpu3,
+    Cpu4,
+    Cpu5,
+    Cpu6,
+}
+
+/// Selects the CPUs of a PCD a run, stop or restart command applies to.
+///
+/// The CPUs are selected by the command id, as listed in the opcode table of [`CommandId`].
+/// The table has no command to restart CPU 0 individually, 0x31 and 0x63 are missing from the cold and warm restart ranges.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CpuSelection {
+    /// All CPUs of the PCD.
+    All,
+    /// A single CPU. CPU 0 can not be restarted individually.
+    Cpu(Cpu),
+}
+
+/// What a run, stop or restart command does with the selected CPUs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CpuCommand {
+    Run,
+    Stop,
+    RestartCold,
+    RestartWarm,
+}
+
+impl From<Cpu> for CpuSelection {
+    fn from(cpu: Cpu) -> Self {
+        Self::Cpu(cpu)
+    }
+}
+
+impl CpuSelection {
+    const ALL: [CpuSelection; 9] = [
+        CpuSelection::All,
+        CpuSelection::Cpu(Cpu::Own),
+        CpuSelection::Cpu(Cpu::Cpu0),
+        CpuSelection::Cpu(Cpu::Cpu1),
+        CpuSelection::Cpu(Cpu::Cpu2),
+        CpuSelection::Cpu(Cpu::Cpu3),
+        CpuSelection::Cpu(Cpu::Cpu4),
+        CpuSelection::Cpu(Cpu::Cpu5),
+        CpuSelection::Cpu(Cpu::Cpu6),
+    ];
+
+    /// Returns the CPUs selected by a run, stop or restart command and the method to be called for the command.
+    pub(crate) fn from_command(command_id: CommandId) -> Option<(CpuCommand, CpuSelection)> {
+        Self::ALL.into_iter().find_map(|cpus| {
+            let command = match command_id {
+                id if id == cpus.run_command() => CpuCommand::Run,
+                id if id == cpus.stop_command() => CpuCommand::Stop,
+                id if Some(id) == cpus.restart_cold_command() => CpuCommand::RestartCold,
+                id if Some(id) == cpus.restart_warm_command() => CpuCommand::RestartWarm,
+                _ => return None,
+            };
+            Some((command, cpus))
+        })
+    }
+
+    pub(crate) fn run_command(self) -> CommandId {
+        match self {
+            CpuSelection::All => CommandId::RunProcedureAllCPUS,
+            CpuSelection::Cpu(Cpu::Own) => CommandId::RunProcedureOwnCPU,
+            CpuSelection::Cpu(Cpu::Cpu0) => CommandId::RunProcedureCPU0,
+            CpuSelection::Cpu(Cpu::Cpu1) => CommandId::RunProcedureCPU1,
+            CpuSelection::Cpu(Cpu::Cpu2) => CommandId::RunProcedureCPU2,
+            CpuSelection::Cpu(Cpu::Cpu3) => CommandId::RunProcedureCPU3,
+            CpuSelection::Cpu(Cpu::Cpu4) => CommandId::RunProcedureCPU4,
+            CpuSelection::Cpu(Cpu::Cpu5) => CommandId::RunProcedureCPU5,
+            CpuSelection::Cpu(Cpu::Cpu6) => CommandId::RunProcedureCPU6,
+        }
+    }
+
+    pub(crate) fn stop_command(self) -> CommandId {
+        match self {
+            CpuSelection::All => CommandId::StopProcedureAllCPUS,
+            CpuSelection::Cpu(Cpu::Own) => CommandId::StopProcedureOwnCPU,
+            CpuSelection::Cpu(Cpu::Cpu0) => CommandId::StopProcedureCPU0,
+            CpuSelection::Cpu(Cpu::Cpu1) => CommandId::StopProcedureCPU1,
+            CpuSelection::Cpu(Cpu::Cpu2) => CommandId::StopProcedureCPU2,
+            CpuSelection::Cpu(Cpu::Cpu3) => CommandId::StopProcedureCPU3,
+            CpuSelection::Cpu(Cpu::Cpu4) => CommandId::StopProcedureCPU4,
+            CpuSelection::Cpu(Cpu::Cpu5) => CommandId::StopProcedureCPU5,
+            CpuSelection::Cpu(Cpu::Cpu6) => CommandId::StopProcedureCPU6,
+        }
+    }
+
+    /// Returns [`None`] for CPU 0, which has no command to be restarted individually.
+    /// Cold restarts of CPU 1 to 6 are 0x32 to 0x37, followed by the own CPU and all CPUs.
+    pub(crate) fn restart_cold_command(self) -> Option<CommandId> {
+        Some(match self {
+            CpuSelection::All => CommandId::RestartColdAllCPUS,
+            CpuSelection::Cpu(Cpu::Own) => CommandId::RestartColdOwnCPU,
+            CpuSelection::Cpu(Cpu::Cpu0) => return None,
+            CpuSelection::Cpu(Cpu::Cpu1) => CommandId::RestartColdCPU1,
+            CpuSelection::Cpu(Cpu::Cpu2) => CommandId::RestartColdCPU2,
+            CpuSelection::Cpu(Cpu::Cpu3) => CommandId::RestartColdCPU3,
+            CpuSelection::Cpu(Cpu::Cpu4) => CommandId::RestartColdCPU4,
+            CpuSelection::Cpu(Cpu::Cpu5) => CommandId::RestartColdCPU5,
+            CpuSelection::Cpu(Cpu::Cpu6) => CommandId::RestartColdCPU6,
+        })
+    }
+
+    /// Returns [`None`] for CPU 0, which has no command to be restarted individually.
+    /// Warm restarts of CPU 1 to 6 are 0x64 to 0x69, followed by the own CPU and all CPUs.
+    pub(crate) fn restart_warm_command(self) -> Option<CommandId> {
+        Some(match self {
+            CpuSelection::All => CommandId::RestartWarmAllCPUS,
+            CpuSelection::Cpu(Cpu::Own) => CommandId::RestartWarmOwnCPU,
+            CpuSelection::Cpu(Cpu::Cpu0) => return None,
+            CpuSelection::Cpu(Cpu::Cpu1) => CommandId::RestartWarmCPU1,
+            CpuSelection::Cpu(Cpu::Cpu2) => CommandId::RestartWarmCPU2,
+            CpuSelection::Cpu(Cpu::Cpu3) => CommandId::RestartWarmCPU3,
+            CpuSelection::Cpu(Cpu::Cpu4) => CommandId::RestartWarmCPU4,
+            CpuSelection::Cpu(Cpu::Cpu5) => CommandId::RestartWarmCPU5,
+            CpuSelection::Cpu(Cpu::Cpu6) => CommandId::RestartWarmCPU6,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The opcodes of the commands, in the order of CPU 0 to 6, the own CPU and all CPUs.
+    fn opcodes(command: impl Fn(CpuSelection) -> Option<CommandId>) -> Vec<Option<u8>> {
+        let cpus = [Cpu::Cpu0, Cpu::Cpu1, Cpu::Cpu2, Cpu::Cpu3, Cpu::Cpu4, Cpu::Cpu5, Cpu::Cpu6, Cpu::Own];
+        let selections = cpus.into_iter().map(CpuSelection::Cpu).chain([CpuSelection::All]);
+        selections.map(|cpus| command(cpus).map(u8::from)).collect()
+    }
+
+    #[test]
+    fn opcode_table() {
+        assert_eq!(opcodes(|cpus| Some(cpus.run_command())), (0x28..=0x30).map(Some).collect::<Vec<_>>());
+        assert_eq!(opcodes(|cpus| Some(cpus.stop_command())), (0x3C..=0x44).map(Some).collect::<Vec<_>>());
+        assert_eq!(opcodes(CpuSelection::restart_cold_command), [None].into_iter().chain((0x32..=0x39).map(Some)).collect::<Vec<_>>());
+        assert_eq!(opcodes(CpuSelection::restart_warm_command), [None].into_iter().chain((0x64..=0x6B).map(Some)).collect::<Vec<_>>());
+
+        for opcode in [0x31, 0x63] {
+            assert_eq!(CpuSelection::from_command(CommandId::from(opcode)), None);
+        }
+        assert_eq!(CpuSelection::from_command(CommandId::from(0x6B)), Some((CpuCommand::RestartWarm, CpuSelection::All)));
+    }
+}
@@ -0,0 +1,127 @@
+//! Access to the memory of a PCD by address instead of by media element.
+//!
+//! [`SBusDangerousClient::read_user_memory`] and [`SBusDangerousClient::write_user_memory`] address the memory of the user program,
+//! [`SBusDangerousClient::read_bytes`] and [`SBusDangerousClient::write_bytes`] the absolute address space of the CPU.
+//! Nothing keeps a write from overwriting the program code or the state of the firmware, which can halt the CPU.
+//! Use it only with a known memory layout, e.g. to read a variable of the user program that is not mapped to a register.
+
+use std::future::Future;
+
+use crate::{
+    client::{acknowledge_result, SBusClient, SBusError},
+    command_id::CommandId,
+    commands::*,
+    consts::*,
+    encoding::*,
+    message::TelegramAttribute,
+};
+
+/// Raw memory access, implemented for every [`SBusClient`].
+pub trait SBusDangerousClient: SBusClient {
+    /// Reads bytes of the user program memory.
+    fn read_user_memory(&self, station: u8, address: u32, length: u8) -> impl Future<Output = Result<Vec<u8>, SBusError>> + Send {
+        async move {
+            validate_memory_input(address, length as usize)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::ReadUserMemory,
+                    ReadUserMemoryRequest { address, length }.encode_to_bytes()?,
+                    TelegramAttribute::Response,
+                )
+                .await?;
+            let res = ReadUserMemoryResponse::decode_from_bytes(&res_body)?;
+            Ok(res.bytes.into())
+        }
+    }
+
+    /// Writes bytes of the user program memory.
+    fn write_user_memory(&self, station: u8, address: u32, bytes: &[u8]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_memory_input(address, bytes.len())?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::WriteUserMemory,
+                    WriteUserMemoryRequest {
+                        address,
+                        bytes: bytes.into(),
+                    }
+                    .encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+
+    /// Reads bytes at an absolute address of the PCD memory.
+    fn read_bytes(&self, station: u8, address: u32, length: u8) -> impl Future<Output = Result<Vec<u8>, SBusError>> + Send {
+        async move {
+            validate_memory_input(address, length as usize)?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::ReadByte,
+                    ReadBytesRequest { address, length }.encode_to_bytes()?,
+                    TelegramAttribute::Response,
+                )
+                .await?;
+            let res = ReadBytesResponse::decode_from_bytes(&res_body)?;
+            Ok(res.bytes.into())
+        }
+    }
+
+    /// Writes bytes at an absolute address of the PCD memory.
+    fn write_bytes(&self, station: u8, address: u32, bytes: &[u8]) -> impl Future<Output = Result<(), SBusError>> + Send {
+        async move {
+            validate_memory_input(address, bytes.len())?;
+            let res_body = self
+                .send_request(
+                    station,
+                    CommandId::WriteByte,
+                    WriteBytesRequest {
+                        address,
+                        bytes: bytes.into(),
+                    }
+                    .encode_to_bytes()?,
+                    TelegramAttribute::Acknowledge,
+                )
+                .await?;
+            acknowledge_result(&res_body)
+        }
+    }
+}
+
+impl<C: SBusClient + ?Sized> SBusDangerousClient for C {}
+
+pub(crate) fn validate_memory_input(address: u32, length: usize) -> Result<(), SBusError> {
+    if length == 0 || length > MEMORY_MAX_REQUEST_LEN as usize {
+        return Err(SBusError::ArgumentsOutOfRange("Length exceeds maximum allowed length"));
+    }
+    u32::checked_add(address, (length - 1) as u32).ok_or(SBusError::ArgumentsOutOfRange("Address + length exceeds device address space"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers memory reads with the low byte of each address.
+    struct MemoryMock;
+
+    impl SBusClient for MemoryMock {
+        async fn send_request(&self, _station: u8, command_id: CommandId, body: Vec<u8>, _response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
+            assert_eq!(command_id, CommandId::ReadUserMemory);
+            let req = ReadUserMemoryRequest::decode_from_bytes(&body)?;
+            let bytes: Vec<u8> = (req.address..req.address + req.length as u32).map(|address| address as u8).collect();
+            Ok(ReadUserMemoryResponse { bytes: bytes.into() }.encode_to_bytes()?)
+        }
+    }
+
+    #[tokio::test]
+    async fn read_user_memory() {
+        assert_eq!(MemoryMock.read_user_memory(0, 0x1FE, 4).await.unwrap(), vec![0xFE, 0xFF, 0x00, 0x01]);
+        assert!(matches!(MemoryMock.read_user_memory(0, u32::MAX, 2).await, Err(SBusError::ArgumentsOutOfRange(_))));
+    }
+}
@@ -66,6 +66,15 @@ impl Encoder {
         self.write_u8(0);
     }
 
+    /// Writes the text as ISO 8859-1, the character set of PCD texts.
+    pub fn write_latin1(&mut self, value: &str) -> EncodeResult {
+        self.buffer.reserve(value.len());
+        for char in value.chars() {
+            self.write_u8(u32::from(char).try_into()?);
+        }
+        Ok(())
+    }
+
     pub fn write_bools(&mut self, values: &[bool]) {
         let byte_length = values.len().div_ceil(8);
         self.buffer.reserve(byte_length);
@@ -189,6 +198,11 @@ impl<'a> Decoder<'a> {
         Ok(text)
     }
 
+    /// Reads `length` characters of ISO 8859-1 text.
+    pub fn read_latin1(&mut self, length: usize) -> DecodeResult<String> {
+        Ok(self.read_bytes(length)?.into_iter().map(char::from).collect())
+    }
+
     pub fn read_bools(&mut self, length: usize) -> DecodeResult<Vec<bool>> {
         let byte_length = length.div_ceil(8);
         let mut values = Vec::with_capacity(length);
@@ -261,4 +275,14 @@ mod tests {
         assert_eq!(decoder.position(), 12);
         assert_eq!(decoder.remaining(), 0);
     }
+    #[test]
+    fn latin1() {
+        let mut encoder = Encoder::new();
+        assert_eq!(encoder.write_latin1("Grüße"), Ok(()));
+        assert_eq!(encoder.write_latin1("€"), Err(EncodeError::Overflow));
+
+        let bytes = encoder.finish();
+        assert_eq!(bytes, vec![b'G', b'r', 0xFC, 0xDF, b'e']);
+        assert_eq!(Decoder::new(&bytes).read_latin1(5), Ok("Grüße".into()));
+    }
 }
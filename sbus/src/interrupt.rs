@@ -0,0 +1,33 @@
+use crate::{client::SBusError, command_id::CommandId};
+
+/// An exception organization block (XOB) that can be triggered by an S-Bus request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interrupt {
+    Xob17,
+    Xob18,
+    Xob19,
+}
+
+impl Interrupt {
+    pub(crate) fn command(self) -> CommandId {
+        match self {
+            Interrupt::Xob17 => CommandId::XOB17Interrupt,
+            Interrupt::Xob18 => CommandId::XOB18Interrupt,
+            Interrupt::Xob19 => CommandId::XOB19Interrupt,
+        }
+    }
+}
+
+/// Selects the interrupt by the number of its XOB. Only XOB 17, 18 and 19 can be triggered.
+impl TryFrom<u8> for Interrupt {
+    type Error = SBusError;
+
+    fn try_from(xob: u8) -> Result<Self, Self::Error> {
+        match xob {
+            17 => Ok(Interrupt::Xob17),
+            18 => Ok(Interrupt::Xob18),
+            19 => Ok(Interrupt::Xob19),
+            _ => Err(SBusError::ArgumentsOutOfRange("Only XOB 17, 18 and 19 can be triggered")),
+        }
+    }
+}
@@ -2,13 +2,44 @@ mod acknowledge;
 mod client;
 mod command_id;
 mod commands;
+mod cpu;
 pub mod consts;
+pub mod dangerous;
 mod encoding;
+mod interrupt;
+mod media;
 mod message;
+mod multimedia;
+mod read_plan;
 mod real_time_clock;
 mod request;
+mod serial_client;
+mod serial_message;
+#[cfg(unix)]
+mod serial_port;
+mod server;
+mod simulator;
+mod tcp_client;
+mod udp_client;
+mod udp_server;
 mod utils;
 
-pub use client::{SBusError, SBusUDPClient};
+pub use acknowledge::Acknowledge;
+pub use client::{RetryPolicy, SBusClient, SBusError};
+pub use command_id::CommandId;
+pub use cpu::{Cpu, CpuSelection};
+pub use interrupt::Interrupt;
+pub use media::{Media, MediaValue, MediaValues};
+pub use message::TelegramAttribute;
+pub use multimedia::{MultimediaItem, MultimediaRequest, MultimediaResult};
+pub use read_plan::{PlannedRead, ReadPlan};
 pub use real_time_clock::RealTimeClock;
+pub use serial_client::{SBusSerialClient, SerialLine, SerialMode};
+#[cfg(unix)]
+pub use serial_port::SerialPort;
+pub use server::{RequestAction, SBusHandler};
+pub use simulator::{SBusSimulator, SimulatorFault};
+pub use tcp_client::SBusTCPClient;
+pub use udp_client::{DiscardedResponse, SBusUDPClient};
+pub use udp_server::SBusUDPServer;
 pub use utils::{ieee_to_sbus_float, sbus_float_to_ieee};
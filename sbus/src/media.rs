@@ -0,0 +1,120 @@
+use std::ops::Range;
+
+use crate::consts::*;
+
+/// A media type of a PCD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Media {
+    Counters,
+    Flags,
+    Inputs,
+    Outputs,
+    Registers,
+    Timers,
+}
+
+impl Media {
+    /// The maximum number of elements that can be read or written in one request.
+    pub fn max_request_len(self) -> u16 {
+        match self {
+            Media::Counters => COUNTERS_MAX_REQUEST_LEN,
+            Media::Flags => FLAGS_MAX_REQUEST_LEN,
+            Media::Inputs => INPUTS_MAX_REQUEST_LEN,
+            Media::Outputs => OUTPUTS_MAX_REQUEST_LEN,
+            Media::Registers => REGISTERS_MAX_REQUEST_LEN,
+            Media::Timers => TIMERS_MAX_REQUEST_LEN,
+        }
+    }
+
+    /// Whether the elements of the media are bits rather than 32 bit integers.
+    pub fn is_bool(self) -> bool {
+        matches!(self, Media::Flags | Media::Inputs | Media::Outputs)
+    }
+
+    /// The number of bytes the values of `length` elements take up in a telegram.
+    pub(crate) fn byte_length(self, length: usize) -> usize {
+        match self.is_bool() {
+            true => length.div_ceil(8),
+            false => length * 4,
+        }
+    }
+}
+
+/// The value of a single element of a media.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaValue {
+    Bool(bool),
+    Integer(i32),
+}
+
+/// The values of consecutive elements of a media.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaValues {
+    /// Values of flags, inputs or outputs.
+    Bools(Vec<bool>),
+    /// Values of counters, registers or timers.
+    Integers(Vec<i32>),
+}
+
+impl MediaValues {
+    /// Creates empty values of the right kind for the media.
+    pub(crate) fn new(media: Media) -> Self {
+        match media.is_bool() {
+            true => MediaValues::Bools(Vec::new()),
+            false => MediaValues::Integers(Vec::new()),
+        }
+    }
+
+    pub fn into_bools(self) -> Option<Vec<bool>> {
+        match self {
+            MediaValues::Bools(values) => Some(values),
+            MediaValues::Integers(_) => None,
+        }
+    }
+
+    pub fn into_integers(self) -> Option<Vec<i32>> {
+        match self {
+            MediaValues::Bools(_) => None,
+            MediaValues::Integers(values) => Some(values),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            MediaValues::Bools(values) => values.len(),
+            MediaValues::Integers(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<MediaValue> {
+        match self {
+            MediaValues::Bools(values) => values.get(index).copied().map(MediaValue::Bool),
+            MediaValues::Integers(values) => values.get(index).copied().map(MediaValue::Integer),
+        }
+    }
+
+    pub(crate) fn slice(&self, range: Range<usize>) -> Self {
+        match self {
+            MediaValues::Bools(values) => MediaValues::Bools(values[range].to_vec()),
+            MediaValues::Integers(values) => MediaValues::Integers(values[range].to_vec()),
+        }
+    }
+
+    /// Appends values of the same kind, values of the other kind are ignored.
+    pub(crate) fn append(&mut self, other: MediaValues) {
+        match (self, other) {
+            (MediaValues::Bools(values), MediaValues::Bools(other)) => values.extend(other),
+            (MediaValues::Integers(values), MediaValues::Integers(other)) => values.extend(other),
+            _ => {}
+        }
+    }
+
+    /// Whether the values are of the right kind for the media.
+    pub(crate) fn fits(&self, media: Media) -> bool {
+        matches!(self, MediaValues::Bools(_)) == media.is_bool()
+    }
+}
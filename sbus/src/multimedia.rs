@@ -0,0 +1,98 @@
+use crate::{
+    acknowledge::Acknowledge,
+    client::{validate_input, SBusError},
+    media::{Media, MediaValues},
+};
+
+/// A read or write of consecutive elements of a media, as part of a [`MultimediaRequest`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MultimediaItem {
+    Read { media: Media, address: u16, length: u8 },
+    Write { media: Media, address: u16, values: MediaValues },
+}
+
+/// The result of a [`MultimediaItem`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MultimediaResult {
+    /// The values of a read.
+    Values(MediaValues),
+    /// The values of a write were written.
+    Written,
+    /// The station refused the item with the acknowledge code.
+    Refused(Acknowledge),
+}
+
+/// Builds a list of reads and writes of different media, to be executed by [`crate::SBusClient::read_write_multimedia`].
+///
+/// ```
+/// use sbus::{Media, MultimediaRequest};
+///
+/// let request = MultimediaRequest::new()
+///     .read(Media::Flags, 100, 16)
+///     .read(Media::Registers, 2000, 4)
+///     .write_registers(2010, &[1, 2]);
+/// assert_eq!(request.items().len(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MultimediaRequest {
+    items: Vec<MultimediaItem>,
+}
+
+impl MultimediaRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read(mut self, media: Media, address: u16, length: u8) -> Self {
+        self.items.push(MultimediaItem::Read { media, address, length });
+        self
+    }
+
+    pub fn write_counters(self, address: u16, values: &[i32]) -> Self {
+        self.write(Media::Counters, address, MediaValues::Integers(values.into()))
+    }
+
+    pub fn write_flags(self, address: u16, values: &[bool]) -> Self {
+        self.write(Media::Flags, address, MediaValues::Bools(values.into()))
+    }
+
+    pub fn write_outputs(self, address: u16, values: &[bool]) -> Self {
+        self.write(Media::Outputs, address, MediaValues::Bools(values.into()))
+    }
+
+    pub fn write_registers(self, address: u16, values: &[i32]) -> Self {
+        self.write(Media::Registers, address, MediaValues::Integers(values.into()))
+    }
+
+    pub fn write_timers(self, address: u16, values: &[i32]) -> Self {
+        self.write(Media::Timers, address, MediaValues::Integers(values.into()))
+    }
+
+    fn write(mut self, media: Media, address: u16, values: MediaValues) -> Self {
+        self.items.push(MultimediaItem::Write { media, address, values });
+        self
+    }
+
+    pub fn items(&self) -> &[MultimediaItem] {
+        &self.items
+    }
+}
+
+/// Checks every item, so that none is sent if one of them is invalid.
+pub(crate) fn validate_multimedia(items: &[MultimediaItem]) -> Result<(), SBusError> {
+    for item in items {
+        match item {
+            MultimediaItem::Read { media, address, length } => validate_input(*address, *length as usize, media.max_request_len())?,
+            MultimediaItem::Write { media, address, values } => {
+                if *media == Media::Inputs {
+                    return Err(SBusError::ArgumentsOutOfRange("Inputs can not be written"));
+                }
+                if !values.fits(*media) {
+                    return Err(SBusError::ArgumentsOutOfRange("Values do not match the media"));
+                }
+                validate_input(*address, values.len(), media.max_request_len())?;
+            }
+        }
+    }
+    Ok(())
+}
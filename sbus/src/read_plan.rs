@@ -0,0 +1,83 @@
+use std::collections::BTreeSet;
+
+use crate::media::Media;
+
+/// A read of consecutive elements, as part of a [`ReadPlan`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlannedRead {
+    pub media: Media,
+    pub address: u16,
+    pub length: u8,
+}
+
+/// The reads that cover a set of scattered elements with as few requests as possible.
+///
+/// Elements of the same media are read together if at most `max_gap` unneeded elements lie between them and the request limit of the media is not exceeded.
+/// The plan is executed with [`crate::SBusClient::read_planned`].
+///
+/// ```
+/// use sbus::{Media, ReadPlan};
+///
+/// let plan = ReadPlan::new([(Media::Registers, 10), (Media::Registers, 14), (Media::Registers, 100), (Media::Flags, 5)], 8);
+/// assert_eq!(plan.reads().len(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReadPlan {
+    items: BTreeSet<(Media, u16)>,
+    reads: Vec<PlannedRead>,
+}
+
+impl ReadPlan {
+    pub fn new(items: impl IntoIterator<Item = (Media, u16)>, max_gap: u16) -> Self {
+        let items: BTreeSet<(Media, u16)> = items.into_iter().collect();
+        let mut reads: Vec<PlannedRead> = Vec::new();
+
+        // The items are sorted by media and address, so each read only has to be extended or closed
+        for &(media, address) in &items {
+            if let Some(read) = reads.last_mut().filter(|read| read.media == media) {
+                let end = read.address as u32 + read.length as u32;
+                let length = address as u32 - read.address as u32 + 1;
+                if address as u32 - end <= max_gap as u32 && length <= media.max_request_len() as u32 {
+                    read.length = length as u8;
+                    continue;
+                }
+            }
+            reads.push(PlannedRead { media, address, length: 1 });
+        }
+
+        Self { items, reads }
+    }
+
+    /// The elements to read, sorted by media and address.
+    pub fn items(&self) -> impl Iterator<Item = (Media, u16)> + '_ {
+        self.items.iter().copied()
+    }
+
+    pub fn reads(&self) -> &[PlannedRead] {
+        &self.reads
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesce_reads() {
+        let items = [(Media::Registers, 40), (Media::Registers, 0), (Media::Registers, 2), (Media::Registers, 10), (Media::Registers, 2), (Media::Flags, 0), (Media::Flags, 128)];
+        let plan = ReadPlan::new(items, 8);
+        assert_eq!(plan.items().count(), 6);
+        assert_eq!(
+            plan.reads(),
+            &[
+                PlannedRead { media: Media::Flags, address: 0, length: 1 },
+                PlannedRead { media: Media::Flags, address: 128, length: 1 },
+                PlannedRead { media: Media::Registers, address: 0, length: 11 },
+                PlannedRead { media: Media::Registers, address: 40, length: 1 },
+            ]
+        );
+
+        let plan = ReadPlan::new((0..100).map(|address| (Media::Registers, address * 2)), 1);
+        assert_eq!(plan.reads().iter().map(|read| read.length).collect::<Vec<u8>>(), vec![31, 31, 31, 31, 31, 31, 7]);
+    }
+}
@@ -0,0 +1,539 @@
+use std::{
+    io,
+    pin::Pin,
+    sync::atomic::{AtomicU32, Ordering},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf},
+    sync::Mutex,
+};
+
+use crate::{
+    client::{response_body, RetryPolicy, SBusClient, SBusError},
+    command_id::CommandId,
+    commands::ReadRegistersRequest,
+    consts::BROADCAST_STATION,
+    encoding::*,
+    media::Media,
+    message::TelegramAttribute,
+    request::Request,
+    serial_message::{SerialMessage, FRAME_SYNC},
+    utils::crc16,
+};
+
+const DEFAULT_BAUD_RATE: u32 = 9600;
+
+/// Bits per character on the line: start bit, 8 data bits, parity bit and stop bit.
+const CHARACTER_BITS: u32 = 11;
+
+/// Characters the break condition that starts a telegram in [`SerialMode::Break`] lasts.
+const BREAK_CHARACTERS: u32 = 4;
+
+/// The break condition lasts at least this long, as the line control calls are not timed exactly.
+const MIN_BREAK_DURATION: Duration = Duration::from_millis(20);
+
+/// Characters the line has to stay quiet after a telegram in [`SerialMode::Parity`] and [`SerialMode::Break`], which have no end marker.
+const FRAME_GAP_CHARACTERS: u32 = 4;
+
+/// The frame gap lasts at least this long, as the operating system and USB adapters can delay characters by a few milliseconds.
+const MIN_FRAME_GAP: Duration = Duration::from_millis(10);
+
+/// The way telegrams are delimited on the serial line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SerialMode {
+    /// Telegrams start with a frame sync character and carry a telegram attribute.
+    Data,
+    /// The station address is sent with the parity bit set, everything else with the parity bit cleared.
+    Parity,
+    /// Telegrams start with a break condition.
+    Break,
+}
+
+/// A serial line the [`SBusSerialClient`] can communicate over, such as a [`crate::SerialPort`].
+///
+/// Only [`SerialMode::Data`] works with the default implementations.
+/// Implement the line control methods to use the other modes.
+pub trait SerialLine: AsyncRead + AsyncWrite + Unpin + Send + 'static {
+    /// Switches the parity bit of the following characters between mark (`true`) and space (`false`).
+    /// Must not return before all previously written characters are transmitted.
+    fn set_mark_parity(&mut self, mark: bool) -> io::Result<()> {
+        _ = mark;
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Parity mode is not supported by this serial line"))
+    }
+
+    /// Starts (`true`) or stops (`false`) transmitting a break condition.
+    fn set_break_condition(&mut self, enabled: bool) -> io::Result<()> {
+        _ = enabled;
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Break mode is not supported by this serial line"))
+    }
+
+    /// Discards everything that was received but not read yet.
+    /// The default implementation reads until no more data is ready, which requires reads that do not start a background operation.
+    fn discard_input(&mut self) -> io::Result<()> {
+        let mut read_buffer = [0; 256];
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            let mut buffer = ReadBuf::new(&mut read_buffer);
+            match Pin::new(&mut *self).poll_read(&mut cx, &mut buffer) {
+                Poll::Ready(Ok(())) if !buffer.filled().is_empty() => continue,
+                // The end of the stream is reported by the next read
+                Poll::Ready(Ok(())) | Poll::Pending => return Ok(()),
+                Poll::Ready(Err(error)) => return Err(error),
+            }
+        }
+    }
+}
+
+/// Client for S-Bus over a serial line, in one of the [`SerialMode`]s.
+/// The line is half-duplex and serial telegrams carry no sequence number, so requests are sent one after another
+/// and anything received before a request is discarded.
+pub struct SBusSerialClient<T> {
+    port: Mutex<T>,
+    mode: SerialMode,
+    baud_rate: AtomicU32,
+    retry_policy: std::sync::Mutex<RetryPolicy>,
+}
+
+impl<T: SerialLine> SBusSerialClient<T> {
+    pub fn new(port: T, mode: SerialMode) -> Self {
+        Self {
+            port: Mutex::new(port),
+            mode,
+            baud_rate: AtomicU32::new(DEFAULT_BAUD_RATE),
+            retry_policy: Default::default(),
+        }
+    }
+
+    /// Returns the baud rate the break duration and frame gap are derived from.
+    pub fn baud_rate(&self) -> u32 {
+        self.baud_rate.load(Ordering::Relaxed)
+    }
+
+    /// Sets the baud rate of the line, which the break duration and frame gap are derived from. Defaults to 9600.
+    /// The baud rate of the line itself is configured on the port.
+    ///
+    /// Panics if the baud rate is zero.
+    pub fn set_baud_rate(&self, baud_rate: u32) {
+        assert_ne!(baud_rate, 0, "Baud rate must not be zero");
+        self.baud_rate.store(baud_rate, Ordering::Relaxed);
+    }
+
+    fn character_time(&self) -> Duration {
+        Duration::from_secs(CHARACTER_BITS.into()) / self.baud_rate()
+    }
+
+    fn break_duration(&self) -> Duration {
+        (self.character_time() * BREAK_CHARACTERS).max(MIN_BREAK_DURATION)
+    }
+
+    fn frame_gap(&self) -> Duration {
+        (self.character_time() * FRAME_GAP_CHARACTERS).max(MIN_FRAME_GAP)
+    }
+
+    /// Returns the timeout and retransmission policy used for requests.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        *self.retry_policy.lock().unwrap()
+    }
+
+    /// Sets the timeout and retransmission policy used for all subsequent requests.
+    pub fn set_retry_policy(&self, retry_policy: RetryPolicy) {
+        *self.retry_policy.lock().unwrap() = retry_policy;
+    }
+
+    async fn write_telegram(&self, port: &mut T, bytes: &[u8]) -> Result<(), SBusError> {
+        // Serial telegrams carry no sequence number, so a late response to an earlier request must not be mistaken for the answer to this one
+        port.discard_input()?;
+
+        match self.mode {
+            SerialMode::Data => port.write_all(bytes).await?,
+            SerialMode::Parity => {
+                port.set_mark_parity(true)?;
+                port.write_all(&bytes[..1]).await?;
+                port.flush().await?;
+                port.set_mark_parity(false)?;
+                port.write_all(&bytes[1..]).await?;
+            }
+            SerialMode::Break => {
+                port.set_break_condition(true)?;
+                tokio::time::sleep(self.break_duration()).await;
+                port.set_break_condition(false)?;
+                port.write_all(bytes).await?;
+            }
+        }
+        port.flush().await?;
+        Ok(())
+    }
+
+    fn encode_request(&self, station: u8, command_id: CommandId, body: Vec<u8>) -> Result<Vec<u8>, SBusError> {
+        let req_bytes = Request {
+            station,
+            command_id,
+            body: body.into(),
+        }
+        .encode_to_bytes()?;
+
+        Ok(match self.mode {
+            SerialMode::Data => SerialMessage {
+                telegram_attribute: TelegramAttribute::Request,
+                body: req_bytes,
+            }
+            .encode_to_bytes()?,
+            SerialMode::Parity | SerialMode::Break => {
+                let checksum = crc16(&req_bytes);
+                let mut req_bytes = req_bytes;
+                req_bytes.extend(checksum.to_be_bytes());
+                req_bytes
+            }
+        })
+    }
+
+    /// Reads until the received bytes form a complete telegram with a valid checksum.
+    /// Responses carry no length, so this only returns once the caller's timeout expires if the telegram is corrupt.
+    ///
+    /// Parity and break mode telegrams carry no telegram attribute either. A two byte telegram is taken for an acknowledge
+    /// if a response was expected and the request calls for a response of another length.
+    async fn read_telegram(&self, port: &mut T, response_type: TelegramAttribute, response_length: Option<usize>) -> Result<(TelegramAttribute, Vec<u8>), SBusError> {
+        let mut buffer = Vec::new();
+        let mut read_buffer = [0; 256];
+        loop {
+            // The checksum can match a prefix of a longer telegram by chance, so the telegram only ends once the line stays quiet
+            let complete = self.mode != SerialMode::Data && checksum_matches(&buffer);
+            let byte_length = match complete {
+                true => tokio::time::timeout(self.frame_gap(), port.read(&mut read_buffer)).await.unwrap_or(Ok(0))?,
+                false => port.read(&mut read_buffer).await?,
+            };
+            if byte_length == 0 && complete {
+                buffer.truncate(buffer.len() - 2);
+                let telegram_attribute = match response_length {
+                    Some(length) if response_type == TelegramAttribute::Response && buffer.len() == 2 && length != 2 => TelegramAttribute::Acknowledge,
+                    _ => response_type,
+                };
+                return Ok((telegram_attribute, buffer));
+            }
+            if byte_length == 0 {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+            buffer.extend_from_slice(&read_buffer[..byte_length]);
+
+            if self.mode == SerialMode::Data {
+                // Discard anything received before the start of the latest frame
+                if let Some(start) = buffer.iter().rposition(|b| *b == FRAME_SYNC) {
+                    buffer.drain(..start);
+                }
+                if let Ok(msg) = SerialMessage::decode_from_bytes(&buffer) {
+                    return Ok((msg.telegram_attribute, msg.body));
+                }
+            }
+        }
+    }
+}
+
+/// Whether the bytes end with a valid checksum of the bytes before it.
+fn checksum_matches(bytes: &[u8]) -> bool {
+    if bytes.len() <= 2 {
+        return false;
+    }
+    let (bytes, checksum) = bytes.split_at(bytes.len() - 2);
+    crc16(bytes) == u16::from_be_bytes([checksum[0], checksum[1]])
+}
+
+/// The length of the response body to a request, for the commands where it follows from the request.
+fn response_length(command_id: CommandId, body: &[u8]) -> Option<usize> {
+    let media = match command_id {
+        CommandId::ReadCounters => Media::Counters,
+        CommandId::ReadFlags => Media::Flags,
+        CommandId::ReadInputs => Media::Inputs,
+        CommandId::ReadOutputs => Media::Outputs,
+        CommandId::ReadRegisters => Media::Registers,
+        CommandId::ReadTimers => Media::Timers,
+        CommandId::ReadDisplayRegister => return Some(4),
+        CommandId::ReadSBusStationNumber => return Some(1),
+        _ => return None,
+    };
+    // All media reads share the layout of a register read
+    let req = ReadRegistersRequest::decode_from_bytes(body).ok()?;
+    Some(media.byte_length(req.length as usize))
+}
+
+impl<T: SerialLine> SBusClient for SBusSerialClient<T> {
+    async fn send_request(&self, station: u8, command_id: CommandId, body: Vec<u8>, response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
+        if station == BROADCAST_STATION {
+            return Err(SBusError::ArgumentsOutOfRange("Broadcasts are not replied to"));
+        }
+
+        let response_length = response_length(command_id, &body);
+        let req_bytes = self.encode_request(station, command_id, body)?;
+
+        // The line is half-duplex, so only one request can be in flight at a time.
+        let mut port = self.port.lock().await;
+
+        let retry_policy = self.retry_policy();
+        let mut retries = 0;
+
+        let (telegram_attribute, res_body) = loop {
+            self.write_telegram(&mut port, &req_bytes).await?;
+
+            match tokio::time::timeout(retry_policy.timeout, self.read_telegram(&mut port, response_type, response_length)).await {
+                Ok(result) => break result?,
+                Err(_) if retries < retry_policy.retries => retries += 1,
+                Err(_) => return Err(SBusError::Timeout),
+            }
+        };
+
+        response_body(telegram_attribute, res_body, response_type)
+    }
+
+    async fn send_broadcast(&self, command_id: CommandId, body: Vec<u8>) -> Result<(), SBusError> {
+        let req_bytes = self.encode_request(BROADCAST_STATION, command_id, body)?;
+        let mut port = self.port.lock().await;
+        self.write_telegram(&mut port, &req_bytes).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex as StdMutex};
+
+    use tokio::io::{duplex, DuplexStream};
+
+    use super::*;
+    use crate::{acknowledge::Acknowledge, commands::*};
+
+    impl SerialLine for DuplexStream {}
+
+    #[derive(Debug, PartialEq)]
+    enum LineEvent {
+        MarkParity(bool),
+        Break(bool),
+        Write(Vec<u8>),
+    }
+
+    /// A serial line that records the line control calls and the written bytes.
+    struct MockLine {
+        stream: DuplexStream,
+        events: Arc<StdMutex<Vec<LineEvent>>>,
+    }
+
+    impl MockLine {
+        fn new() -> (Self, Arc<StdMutex<Vec<LineEvent>>>, DuplexStream) {
+            let (stream, station) = duplex(256);
+            let events = Arc::new(StdMutex::new(Vec::new()));
+            (Self { stream, events: events.clone() }, events, station)
+        }
+    }
+
+    impl AsyncRead for MockLine {
+        fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.stream).poll_read(cx, buf)
+        }
+    }
+
+    impl AsyncWrite for MockLine {
+        fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            let result = Pin::new(&mut self.stream).poll_write(cx, buf);
+            if let Poll::Ready(Ok(byte_length)) = result {
+                let mut events = self.events.lock().unwrap();
+                match events.last_mut() {
+                    Some(LineEvent::Write(bytes)) => bytes.extend_from_slice(&buf[..byte_length]),
+                    _ => events.push(LineEvent::Write(buf[..byte_length].into())),
+                }
+            }
+            result
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.stream).poll_flush(cx)
+        }
+
+        fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.stream).poll_shutdown(cx)
+        }
+    }
+
+    impl SerialLine for MockLine {
+        fn set_mark_parity(&mut self, mark: bool) -> io::Result<()> {
+            self.events.lock().unwrap().push(LineEvent::MarkParity(mark));
+            Ok(())
+        }
+
+        fn set_break_condition(&mut self, enabled: bool) -> io::Result<()> {
+            self.events.lock().unwrap().push(LineEvent::Break(enabled));
+            Ok(())
+        }
+    }
+
+    fn with_checksum(bytes: &[u8]) -> Vec<u8> {
+        let mut bytes = bytes.to_vec();
+        bytes.extend(crc16(&bytes).to_be_bytes());
+        bytes
+    }
+
+    #[tokio::test]
+    async fn data_mode() {
+        let (line, mut station) = duplex(256);
+        let client = SBusSerialClient::new(line, SerialMode::Data);
+
+        let station = tokio::spawn(async move {
+            let mut buffer = [0; 256];
+            let byte_length = station.read(&mut buffer).await.unwrap();
+            let msg = SerialMessage::decode_from_bytes(&buffer[..byte_length]).unwrap();
+            assert_eq!(msg.telegram_attribute, TelegramAttribute::Request);
+
+            let req = Request::decode_from_bytes(&msg.body).unwrap();
+            assert_eq!(req.station, 10);
+            assert_eq!(req.command_id, CommandId::ReadRegisters);
+            assert_eq!(ReadRegistersRequest::decode_from_bytes(&req.body), Ok(ReadRegistersRequest { address: 0xB5, length: 2 }));
+
+            let res = SerialMessage {
+                telegram_attribute: TelegramAttribute::Response,
+                body: ReadRegistersResponse {
+                    values: vec![0xB5, 0xC5].into(),
+                }
+                .encode_to_bytes()
+                .unwrap(),
+            };
+            station.write_all(&res.encode_to_bytes().unwrap()).await.unwrap();
+        });
+
+        assert_eq!(client.read_registers(10, 0xB5, 2).await.unwrap(), vec![0xB5, 0xC5]);
+        station.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn discard_late_response() {
+        let (line, mut station) = duplex(256);
+        let client = SBusSerialClient::new(line, SerialMode::Data);
+
+        let late_res = SerialMessage {
+            telegram_attribute: TelegramAttribute::Response,
+            body: ReadSBusStationNumberResponse { station: 1 }.encode_to_bytes().unwrap(),
+        };
+        station.write_all(&late_res.encode_to_bytes().unwrap()).await.unwrap();
+
+        let station = tokio::spawn(async move {
+            let mut buffer = [0; 256];
+            let byte_length = station.read(&mut buffer).await.unwrap();
+            let msg = SerialMessage::decode_from_bytes(&buffer[..byte_length]).unwrap();
+            assert_eq!(Request::decode_from_bytes(&msg.body).unwrap().command_id, CommandId::ReadSBusStationNumber);
+
+            let res = SerialMessage {
+                telegram_attribute: TelegramAttribute::Response,
+                body: ReadSBusStationNumberResponse { station: 2 }.encode_to_bytes().unwrap(),
+            };
+            station.write_all(&res.encode_to_bytes().unwrap()).await.unwrap();
+        });
+
+        assert_eq!(client.read_sbus_station_number().await.unwrap(), 2);
+        station.await.unwrap();
+    }
+
+    // The paused clock only advances when all tasks wait, which keeps the station's pause within the frame gap
+    #[tokio::test(start_paused = true)]
+    async fn parity_mode() {
+        let (line, events, mut station) = MockLine::new();
+        let client = SBusSerialClient::new(line, SerialMode::Parity);
+
+        // The checksum of the first value makes the response up to the middle of the second value look complete
+        let first = 0x12345678;
+        let second = (crc16(&i32::to_be_bytes(first)) as i32) << 16 | 0x9ABC;
+        let res_bytes = ReadRegistersResponse { values: vec![first, second].into() }.encode_to_bytes().unwrap();
+        assert!(checksum_matches(&res_bytes[..6]));
+
+        let station = tokio::spawn(async move {
+            let mut buffer = [0; 7];
+            station.read_exact(&mut buffer).await.unwrap();
+            assert!(checksum_matches(&buffer));
+            let req = Request::decode_from_bytes(&buffer[..5]).unwrap();
+            assert_eq!((req.station, req.command_id), (10, CommandId::ReadRegisters));
+
+            station.write_all(&res_bytes[..6]).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            station.write_all(&with_checksum(&res_bytes)[6..]).await.unwrap();
+
+            // A refusal carries no telegram attribute and is recognized by its length
+            station.read_exact(&mut buffer).await.unwrap();
+            station.write_all(&with_checksum(&Acknowledge::Nak.encode_to_bytes().unwrap())).await.unwrap();
+        });
+
+        assert_eq!(client.read_registers(10, 0, 2).await.unwrap(), vec![first, second]);
+        assert!(matches!(client.read_registers(10, 0, 2).await, Err(SBusError::Nak(Acknowledge::Nak))));
+        station.await.unwrap();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[..3], [LineEvent::MarkParity(true), LineEvent::Write(vec![10]), LineEvent::MarkParity(false)]);
+        assert!(matches!(&events[3], LineEvent::Write(bytes) if bytes.len() == 6));
+    }
+
+    #[tokio::test]
+    async fn break_mode() {
+        let (line, events, mut station) = MockLine::new();
+        let client = SBusSerialClient::new(line, SerialMode::Break);
+
+        let station = tokio::spawn(async move {
+            let mut buffer = [0; 4];
+            station.read_exact(&mut buffer).await.unwrap();
+            assert!(checksum_matches(&buffer));
+            station.write_all(&with_checksum(&[0, 0, 0, 42])).await.unwrap();
+            buffer
+        });
+
+        assert_eq!(client.read_display_register(10).await.unwrap(), 42);
+        let req_bytes = station.await.unwrap();
+        assert_eq!(
+            *events.lock().unwrap(),
+            [LineEvent::Break(true), LineEvent::Break(false), LineEvent::Write(req_bytes.into())]
+        );
+    }
+
+    #[test]
+    fn timing_follows_baud_rate() {
+        let (line, _station) = duplex(256);
+        let client = SBusSerialClient::new(line, SerialMode::Break);
+
+        // At common baud rates a few characters take less than the minimum
+        assert_eq!(client.break_duration(), MIN_BREAK_DURATION);
+        assert_eq!(client.frame_gap(), MIN_FRAME_GAP);
+        client.set_baud_rate(115200);
+        assert_eq!(client.frame_gap(), MIN_FRAME_GAP);
+
+        // 4 characters of 11 bits at 1200 baud take 36.7 ms
+        client.set_baud_rate(1200);
+        assert_eq!(client.break_duration().as_micros(), 36666);
+        assert_eq!(client.frame_gap().as_micros(), 36666);
+    }
+
+    // A pause longer than the minimum frame gap does not end a telegram on a slow line
+    #[tokio::test(start_paused = true)]
+    async fn parity_mode_on_slow_line() {
+        let (line, _events, mut station) = MockLine::new();
+        let client = SBusSerialClient::new(line, SerialMode::Parity);
+        client.set_baud_rate(1200);
+
+        // The checksum of the first value makes the response up to the middle of the second value look complete
+        let first = 0x12345678;
+        let second = (crc16(&i32::to_be_bytes(first)) as i32) << 16 | 0x9ABC;
+        let res_bytes = ReadRegistersResponse { values: vec![first, second].into() }.encode_to_bytes().unwrap();
+
+        let station = tokio::spawn(async move {
+            let mut buffer = [0; 7];
+            station.read_exact(&mut buffer).await.unwrap();
+            station.write_all(&res_bytes[..6]).await.unwrap();
+            tokio::time::sleep(MIN_FRAME_GAP * 2).await;
+            station.write_all(&with_checksum(&res_bytes)[6..]).await.unwrap();
+        });
+
+        assert_eq!(client.read_registers(10, 0, 2).await.unwrap(), vec![first, second]);
+        station.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn parity_mode_requires_line_support() {
+        let (line, _station) = duplex(256);
+        let client = SBusSerialClient::new(line, SerialMode::Parity);
+
+        assert!(matches!(client.read_display_register(0).await, Err(SBusError::IO(_))));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{encoding::*, message::TelegramAttribute, utils::crc16};
+
+pub(crate) const FRAME_SYNC: u8 = 0xB5;
+const ESCAPE: u8 = 0xC5;
+
+/// A telegram framed for serial S-Bus in data mode.
+/// The frame starts with the frame sync character, every following occurrence of the
+/// frame sync or escape character is replaced by the escape character and an index.
+#[derive(PartialEq, Debug)]
+pub struct SerialMessage {
+    pub telegram_attribute: TelegramAttribute,
+    pub body: Vec<u8>,
+}
+
+impl Encodable for SerialMessage {
+    fn encode(&self, encoder: &mut Encoder) -> EncodeResult {
+        let mut pre_encoder = Encoder::new();
+
+        pre_encoder.write_u8(FRAME_SYNC);
+        pre_encoder.write_u8(self.telegram_attribute.into());
+        pre_encoder.write_bytes(&self.body);
+
+        let mut bytes = pre_encoder.finish();
+        let checksum = crc16(&bytes);
+        bytes.extend(checksum.to_be_bytes());
+
+        encoder.reserve(bytes.len());
+        encoder.write_u8(FRAME_SYNC);
+        for byte in &bytes[1..] {
+            match *byte {
+                FRAME_SYNC => encoder.write_bytes(&[ESCAPE, 0x00]),
+                ESCAPE => encoder.write_bytes(&[ESCAPE, 0x01]),
+                byte => encoder.write_u8(byte),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Decodable<Self> for SerialMessage {
+    fn decode(decoder: &mut Decoder) -> DecodeResult<Self> {
+        if decoder.read_u8()? != FRAME_SYNC {
+            return Err(DecodeError::InvalidData("Missing frame sync"));
+        }
+
+        let mut bytes = vec![FRAME_SYNC];
+        while decoder.remaining() > 0 {
+            match decoder.read_u8()? {
+                FRAME_SYNC => return Err(DecodeError::InvalidData("Unexpected frame sync")),
+                ESCAPE => match decoder.read_u8()? {
+                    0x00 => bytes.push(FRAME_SYNC),
+                    0x01 => bytes.push(ESCAPE),
+                    _ => return Err(DecodeError::InvalidData("Invalid escape sequence")),
+                },
+                byte => bytes.push(byte),
+            }
+        }
+
+        // Frame sync, telegram attribute and checksum
+        if bytes.len() < 4 {
+            return Err(DecodeError::MissingData);
+        }
+
+        let (bytes, checksum) = bytes.split_at(bytes.len() - 2);
+        if crc16(bytes) != u16::from_be_bytes([checksum[0], checksum[1]]) {
+            return Err(DecodeError::InvalidData("Checksum mismatch"));
+        }
+
+        Ok(Self {
+            telegram_attribute: bytes[1].into(),
+            body: bytes[2..].into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_stuffing() {
+        let msg = SerialMessage {
+            telegram_attribute: TelegramAttribute::Response,
+            body: vec![0x01, FRAME_SYNC, ESCAPE, 0x02],
+        };
+
+        let bytes = msg.encode_to_bytes().unwrap();
+
+        assert_eq!(bytes[0], FRAME_SYNC);
+        assert!(!bytes[1..].contains(&FRAME_SYNC));
+        assert_eq!(&bytes[2..7], &[0x01, ESCAPE, 0x00, ESCAPE, 0x01]);
+        assert_eq!(SerialMessage::decode_from_bytes(&bytes), Ok(msg));
+        assert!(SerialMessage::decode_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
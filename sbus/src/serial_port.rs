@@ -0,0 +1,240 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Write},
+    os::{
+        fd::AsRawFd,
+        unix::fs::OpenOptionsExt,
+    },
+    path::Path,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use tokio::io::{unix::AsyncFd, AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::serial_client::SerialLine;
+
+/// A serial port or pseudo-terminal in raw mode, supporting all [`crate::SerialMode`]s.
+///
+/// The port keeps its configured baud rate, parity mode additionally requires Linux.
+pub struct SerialPort {
+    file: AsyncFd<File>,
+}
+
+impl SerialPort {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY)
+            .open(path)?;
+        Self::from_file(file)
+    }
+
+    /// Takes over an already opened serial port or pseudo-terminal.
+    pub fn from_file(file: File) -> io::Result<Self> {
+        let fd = file.as_raw_fd();
+        // SAFETY: the file descriptor stays open while the file is borrowed, and the termios structure is filled by tcgetattr before it is read
+        unsafe {
+            let mut termios = std::mem::zeroed::<libc::termios>();
+            check(libc::tcgetattr(fd, &mut termios))?;
+            libc::cfmakeraw(&mut termios);
+            check(libc::tcsetattr(fd, libc::TCSANOW, &termios))?;
+
+            let flags = libc::fcntl(fd, libc::F_GETFL);
+            check(flags)?;
+            check(libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK))?;
+        }
+        Ok(Self { file: AsyncFd::new(file)? })
+    }
+}
+
+fn check(result: libc::c_int) -> io::Result<()> {
+    match result {
+        -1 => Err(io::Error::last_os_error()),
+        _ => Ok(()),
+    }
+}
+
+impl AsyncRead for SerialPort {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        loop {
+            let mut guard = ready!(self.file.poll_read_ready(cx))?;
+            let unfilled = buf.initialize_unfilled();
+            if let Ok(result) = guard.try_io(|file| file.get_ref().read(unfilled)) {
+                let byte_length = result?;
+                buf.advance(byte_length);
+                return Poll::Ready(Ok(()));
+            }
+        }
+    }
+}
+
+impl AsyncWrite for SerialPort {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        loop {
+            let mut guard = ready!(self.file.poll_write_ready(cx))?;
+            if let Ok(result) = guard.try_io(|file| file.get_ref().write(buf)) {
+                return Poll::Ready(result);
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl SerialLine for SerialPort {
+    #[cfg(target_os = "linux")]
+    fn set_mark_parity(&mut self, mark: bool) -> io::Result<()> {
+        let fd = self.file.as_raw_fd();
+        // SAFETY: the termios structure is filled by tcgetattr before it is read
+        unsafe {
+            let mut termios = std::mem::zeroed::<libc::termios>();
+            check(libc::tcgetattr(fd, &mut termios))?;
+            // With stick parity, the odd parity flag selects mark rather than space parity
+            termios.c_cflag |= libc::PARENB | libc::CMSPAR;
+            match mark {
+                true => termios.c_cflag |= libc::PARODD,
+                false => termios.c_cflag &= !libc::PARODD,
+            }
+            // Waits until the characters written so far are transmitted with the previous parity
+            check(libc::tcsetattr(fd, libc::TCSADRAIN, &termios))
+        }
+    }
+
+    fn set_break_condition(&mut self, enabled: bool) -> io::Result<()> {
+        let request = match enabled {
+            true => libc::TIOCSBRK,
+            false => libc::TIOCCBRK,
+        };
+        // SAFETY: the request takes no argument
+        check(unsafe { libc::ioctl(self.file.as_raw_fd(), request) })
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        // SAFETY: tcflush only takes the file descriptor
+        check(unsafe { libc::tcflush(self.file.as_raw_fd(), libc::TCIFLUSH) })
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::os::fd::{FromRawFd, RawFd};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::{
+        client::SBusClient,
+        command_id::CommandId,
+        commands::*,
+        encoding::*,
+        message::TelegramAttribute,
+        request::Request,
+        serial_client::{SBusSerialClient, SerialMode},
+        serial_message::SerialMessage,
+        utils::crc16,
+    };
+
+    /// Opens a pseudo-terminal and returns its controlling side, which plays the station, and the terminal.
+    fn pty_pair() -> (SerialPort, SerialPort) {
+        let (mut controller, mut terminal) = (0, 0);
+        // SAFETY: openpty returns two new file descriptors, which are then owned by the files
+        unsafe {
+            assert_eq!(libc::openpty(&mut controller, &mut terminal, std::ptr::null_mut(), std::ptr::null(), std::ptr::null()), 0);
+            (
+                SerialPort::from_file(File::from_raw_fd(controller)).unwrap(),
+                SerialPort::from_file(File::from_raw_fd(terminal)).unwrap(),
+            )
+        }
+    }
+
+    fn termios(fd: RawFd) -> libc::termios {
+        // SAFETY: the termios structure is filled by tcgetattr before it is read
+        unsafe {
+            let mut termios = std::mem::zeroed::<libc::termios>();
+            assert_eq!(libc::tcgetattr(fd, &mut termios), 0);
+            termios
+        }
+    }
+
+    #[tokio::test]
+    async fn data_mode_over_pty() {
+        let (mut station, line) = pty_pair();
+        let client = SBusSerialClient::new(line, SerialMode::Data);
+
+        let station = tokio::spawn(async move {
+            for value in [1, 2] {
+                let mut buffer = [0; 256];
+                let byte_length = station.read(&mut buffer).await.unwrap();
+                let msg = SerialMessage::decode_from_bytes(&buffer[..byte_length]).unwrap();
+                let req = Request::decode_from_bytes(&msg.body).unwrap();
+                assert_eq!(req.command_id, CommandId::ReadDisplayRegister);
+
+                let res = SerialMessage {
+                    telegram_attribute: TelegramAttribute::Response,
+                    body: ReadDisplayRegisterResponse { register: value }.encode_to_bytes().unwrap(),
+                };
+                station.write_all(&res.encode_to_bytes().unwrap()).await.unwrap();
+            }
+            station
+        });
+
+        assert_eq!(client.read_display_register(0).await.unwrap(), 1);
+        assert_eq!(client.read_display_register(0).await.unwrap(), 2);
+        station.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn parity_mode_over_pty() {
+        let (mut station, line) = pty_pair();
+        let fd = line.file.as_raw_fd();
+        let client = SBusSerialClient::new(line, SerialMode::Parity);
+
+        let station = tokio::spawn(async move {
+            for value in [1, 2] {
+                let mut buffer = [0; 4];
+                station.read_exact(&mut buffer).await.unwrap();
+                assert_eq!(buffer[..2], [10, CommandId::ReadDisplayRegister.into()]);
+
+                let mut res_bytes = ReadDisplayRegisterResponse { register: value }.encode_to_bytes().unwrap();
+                res_bytes.extend(crc16(&res_bytes).to_be_bytes());
+                station.write_all(&res_bytes).await.unwrap();
+            }
+            station
+        });
+
+        // The line is ready for the next request after the frame gap, although the telegram had no end marker
+        assert_eq!(client.read_display_register(10).await.unwrap(), 1);
+        assert_eq!(client.read_display_register(10).await.unwrap(), 2);
+        // The terminal is hung up once the station closes its side
+        let _station = station.await.unwrap();
+
+        let termios = termios(fd);
+        // Pseudo-terminals keep the stick parity flags, but always clear the parity enable flag
+        assert_eq!(termios.c_cflag & (libc::CMSPAR | libc::PARODD), libc::CMSPAR);
+    }
+
+    #[tokio::test]
+    async fn break_mode_over_pty() {
+        let (mut station, line) = pty_pair();
+        let client = SBusSerialClient::new(line, SerialMode::Break);
+
+        let station = tokio::spawn(async move {
+            let mut buffer = [0; 4];
+            station.read_exact(&mut buffer).await.unwrap();
+            station.write_all(&[0, 0, 0, 42]).await.unwrap();
+            station.write_all(&crc16(&[0, 0, 0, 42]).to_be_bytes()).await.unwrap();
+            station
+        });
+
+        assert_eq!(client.read_display_register(10).await.unwrap(), 42);
+        station.await.unwrap();
+    }
+}
@@ -0,0 +1,466 @@
+use std::future::Future;
+
+use crate::{acknowledge::Acknowledge, client::validate_input, dangerous::validate_memory_input, command_id::CommandId, commands::*, consts::*, cpu::{CpuCommand, CpuSelection}, encoding::*, interrupt::Interrupt, request::Request, RealTimeClock};
+
+/// What a server does with a request before it is dispatched to the handler methods.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestAction {
+    /// Dispatch the request to the matching handler method.
+    Dispatch,
+    /// Reply with the acknowledge code without dispatching the request.
+    Refuse(Acknowledge),
+    /// Drop the request without a reply.
+    Ignore,
+}
+
+/// Handles the requests received by an S-Bus server.
+///
+/// Every method refuses the request with [`Acknowledge::Nak`] unless implemented.
+/// Read methods return the values to respond with, write methods the acknowledge code to reply with.
+/// The request arguments are validated against the limits in [`crate::consts`] before a method is called.
+#[allow(unused_variables)]
+pub trait SBusHandler: Send + Sync + 'static {
+    /// Called for every request before it is decoded and dispatched.
+    fn filter_request(&self, station: u8, command_id: CommandId) -> impl Future<Output = RequestAction> + Send {
+        async { RequestAction::Dispatch }
+    }
+
+    fn read_real_time_clock(&self, station: u8) -> impl Future<Output = Result<RealTimeClock, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_display_register(&self, station: u8) -> impl Future<Output = Result<u32, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_firmware_version(&self, station: u8) -> impl Future<Output = Result<String, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_sbus_station_number(&self) -> impl Future<Output = Result<u8, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_halt_failure_register(&self, station: u8) -> impl Future<Output = Result<u32, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_counters(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<i32>, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_flags(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<bool>, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_inputs(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<bool>, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_outputs(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<bool>, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_registers(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<i32>, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_timers(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<i32>, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_user_eeprom_registers(&self, station: u8, address: u16, length: u8) -> impl Future<Output = Result<Vec<i32>, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn write_real_time_clock(&self, station: u8, rtc: RealTimeClock) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn write_counters(&self, station: u8, address: u16, values: Vec<i32>) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn write_flags(&self, station: u8, address: u16, values: Vec<bool>) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn write_outputs(&self, station: u8, address: u16, values: Vec<bool>) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn write_registers(&self, station: u8, address: u16, values: Vec<i32>) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn write_timers(&self, station: u8, address: u16, values: Vec<i32>) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn write_user_eeprom_registers(&self, station: u8, address: u16, values: Vec<i32>) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn clear_all_fort(&self, station: u8) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn clear_flags(&self, station: u8) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn clear_outputs(&self, station: u8) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn clear_registers(&self, station: u8) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn clear_timers(&self, station: u8) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    /// Clears the history table and the halt failure register.
+    fn clear_history(&self, station: u8) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn trigger_interrupt(&self, station: u8, interrupt: Interrupt) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn read_user_memory(&self, station: u8, address: u32, length: u8) -> impl Future<Output = Result<Vec<u8>, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn write_user_memory(&self, station: u8, address: u32, bytes: Vec<u8>) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    /// Returns the bytes at an absolute address of the memory.
+    fn read_bytes(&self, station: u8, address: u32, length: u8) -> impl Future<Output = Result<Vec<u8>, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    /// Writes bytes at an absolute address of the memory.
+    fn write_bytes(&self, station: u8, address: u32, bytes: Vec<u8>) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn run_cpu(&self, station: u8, cpus: CpuSelection) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn stop_cpu(&self, station: u8, cpus: CpuSelection) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    /// Never called for CPU 0 alone, which has no command to be restarted individually.
+    fn restart_cpu_cold(&self, station: u8, cpus: CpuSelection) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    /// Never called for CPU 0 alone, which has no command to be restarted individually.
+    fn restart_cpu_warm(&self, station: u8, cpus: CpuSelection) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn read_data_block(&self, station: u8, block: u16, offset: u16, length: u8) -> impl Future<Output = Result<Vec<i32>, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn read_dbx(&self, station: u8, block: u16, offset: u16, length: u8) -> impl Future<Output = Result<Vec<i32>, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn write_data_block(&self, station: u8, block: u16, offset: u16, values: Vec<i32>) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn make_data_block(&self, station: u8, block: u16, length: u16) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn clear_data_block(&self, station: u8, block: u16) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    /// Returns `length` characters of the text. The characters must be of ISO 8859-1.
+    fn read_text(&self, station: u8, text: u16, offset: u16, length: u8) -> impl Future<Output = Result<String, Acknowledge>> + Send {
+        async { Err(Acknowledge::Nak) }
+    }
+
+    fn write_text(&self, station: u8, text: u16, offset: u16, value: String) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn make_text(&self, station: u8, text: u16, length: u16) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+
+    fn clear_text(&self, station: u8, text: u16) -> impl Future<Output = Acknowledge> + Send {
+        async { Acknowledge::Nak }
+    }
+}
+
+/// The reply to a request, independent of the transport.
+#[derive(PartialEq, Debug)]
+pub(crate) enum Reply {
+    Response(Vec<u8>),
+    Acknowledge(Acknowledge),
+}
+
+impl From<Result<Vec<u8>, EncodeError>> for Reply {
+    fn from(value: Result<Vec<u8>, EncodeError>) -> Self {
+        match value {
+            Ok(body) => Self::Response(body),
+            Err(_) => Self::Acknowledge(Acknowledge::Nak),
+        }
+    }
+}
+
+/// Decodes the body of a request, calls the matching handler method and encodes its result.
+/// Requests that can not be decoded or are out of range are refused with [`Acknowledge::Nak`].
+/// Returns [`None`] if the request should not be replied to, which includes all requests to the [`BROADCAST_STATION`].
+pub(crate) async fn dispatch<H: SBusHandler>(handler: &H, req: &Request<'_>) -> Option<Reply> {
+    let reply = match handler.filter_request(req.station, req.command_id).await {
+        RequestAction::Dispatch => match dispatch_decoded(handler, req).await {
+            Ok(reply) => reply,
+            Err(_) => Reply::Acknowledge(Acknowledge::Nak),
+        },
+        RequestAction::Refuse(ack) => Reply::Acknowledge(ack),
+        RequestAction::Ignore => return None,
+    };
+
+    // Replies to a broadcast from every station would collide
+    (req.station != BROADCAST_STATION).then_some(reply)
+}
+
+async fn dispatch_decoded<H: SBusHandler>(handler: &H, req: &Request<'_>) -> DecodeResult<Reply> {
+    fn validate(address: u16, length: usize, max_length: u16) -> DecodeResult<()> {
+        validate_input(address, length, max_length).map_err(|_| DecodeError::InvalidData("Arguments out of range"))
+    }
+
+    fn validate_memory(address: u32, length: usize) -> DecodeResult<()> {
+        validate_memory_input(address, length).map_err(|_| DecodeError::InvalidData("Arguments out of range"))
+    }
+
+    let station = req.station;
+
+    let reply = match req.command_id {
+        CommandId::ReadRealTimeClock => match handler.read_real_time_clock(station).await {
+            Ok(rtc) => ReadRealTimeClockResponse { rtc }.encode_to_bytes().into(),
+            Err(ack) => Reply::Acknowledge(ack),
+        },
+        CommandId::ReadDisplayRegister => match handler.read_display_register(station).await {
+            Ok(register) => ReadDisplayRegisterResponse { register }.encode_to_bytes().into(),
+            Err(ack) => Reply::Acknowledge(ack),
+        },
+        CommandId::ReadFirmwareVersion => match handler.read_firmware_version(station).await {
+            Ok(version) => ReadFirmwareVersionResponse { version: version.into() }.encode_to_bytes().into(),
+            Err(ack) => Reply::Acknowledge(ack),
+        },
+        CommandId::ReadSBusStationNumber => match handler.read_sbus_station_number().await {
+            Ok(station) => ReadSBusStationNumberResponse { station }.encode_to_bytes().into(),
+            Err(ack) => Reply::Acknowledge(ack),
+        },
+        CommandId::ReadHaltFailureRegister => match handler.read_halt_failure_register(station).await {
+            Ok(register) => ReadHaltFailureRegisterResponse { register }.encode_to_bytes().into(),
+            Err(ack) => Reply::Acknowledge(ack),
+        },
+        CommandId::ReadCounters => {
+            let req = ReadCountersRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.length as usize, COUNTERS_MAX_REQUEST_LEN)?;
+            match handler.read_counters(station, req.address, req.length).await {
+                Ok(values) => ReadCountersResponse { values: values.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::ReadFlags => {
+            let req = ReadFlagsRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.length as usize, FLAGS_MAX_REQUEST_LEN)?;
+            match handler.read_flags(station, req.address, req.length).await {
+                Ok(values) => ReadFlagsResponse { values: values.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::ReadInputs => {
+            let req = ReadInputsRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.length as usize, INPUTS_MAX_REQUEST_LEN)?;
+            match handler.read_inputs(station, req.address, req.length).await {
+                Ok(values) => ReadInputsResponse { values: values.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::ReadOutputs => {
+            let req = ReadOutputsRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.length as usize, OUTPUTS_MAX_REQUEST_LEN)?;
+            match handler.read_outputs(station, req.address, req.length).await {
+                Ok(values) => ReadOutputsResponse { values: values.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::ReadRegisters => {
+            let req = ReadRegistersRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.length as usize, REGISTERS_MAX_REQUEST_LEN)?;
+            match handler.read_registers(station, req.address, req.length).await {
+                Ok(values) => ReadRegistersResponse { values: values.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::ReadTimers => {
+            let req = ReadTimersRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.length as usize, TIMERS_MAX_REQUEST_LEN)?;
+            match handler.read_timers(station, req.address, req.length).await {
+                Ok(values) => ReadTimersResponse { values: values.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::ReadUserEEPROMRegister => {
+            let req = ReadUserEepromRegistersRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.length as usize, USER_EEPROM_REGISTERS_MAX_REQUEST_LEN)?;
+            match handler.read_user_eeprom_registers(station, req.address, req.length).await {
+                Ok(values) => ReadUserEepromRegistersResponse { values: values.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::WriteRealTimeClock => {
+            let req = WriteRealTimeClockRequest::decode_from_bytes(&req.body)?;
+            Reply::Acknowledge(handler.write_real_time_clock(station, req.rtc).await)
+        }
+        CommandId::WriteCounters => {
+            let req = WriteCountersRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.values.len(), COUNTERS_MAX_REQUEST_LEN)?;
+            Reply::Acknowledge(handler.write_counters(station, req.address, req.values.into()).await)
+        }
+        CommandId::WriteFlags => {
+            let req = WriteFlagsRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.values.len(), FLAGS_MAX_REQUEST_LEN)?;
+            Reply::Acknowledge(handler.write_flags(station, req.address, req.values.into()).await)
+        }
+        CommandId::WriteOutputs => {
+            let req = WriteOutputsRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.values.len(), OUTPUTS_MAX_REQUEST_LEN)?;
+            Reply::Acknowledge(handler.write_outputs(station, req.address, req.values.into()).await)
+        }
+        CommandId::WriteRegisters => {
+            let req = WriteRegistersRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.values.len(), REGISTERS_MAX_REQUEST_LEN)?;
+            Reply::Acknowledge(handler.write_registers(station, req.address, req.values.into()).await)
+        }
+        CommandId::WriteTimers => {
+            let req = WriteTimersRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.values.len(), TIMERS_MAX_REQUEST_LEN)?;
+            Reply::Acknowledge(handler.write_timers(station, req.address, req.values.into()).await)
+        }
+        CommandId::WriteUserEEPROMRegister => {
+            let req = WriteUserEepromRegistersRequest::decode_from_bytes(&req.body)?;
+            validate(req.address, req.values.len(), USER_EEPROM_REGISTERS_MAX_REQUEST_LEN)?;
+            Reply::Acknowledge(handler.write_user_eeprom_registers(station, req.address, req.values.into()).await)
+        }
+        CommandId::ClearAllFORT => Reply::Acknowledge(handler.clear_all_fort(station).await),
+        CommandId::ClearFlags => Reply::Acknowledge(handler.clear_flags(station).await),
+        CommandId::ClearOutputs => Reply::Acknowledge(handler.clear_outputs(station).await),
+        CommandId::ClearRegisters => Reply::Acknowledge(handler.clear_registers(station).await),
+        CommandId::ClearTimers => Reply::Acknowledge(handler.clear_timers(station).await),
+        CommandId::ClearHistoryFailure => Reply::Acknowledge(handler.clear_history(station).await),
+        CommandId::XOB17Interrupt => Reply::Acknowledge(handler.trigger_interrupt(station, Interrupt::Xob17).await),
+        CommandId::XOB18Interrupt => Reply::Acknowledge(handler.trigger_interrupt(station, Interrupt::Xob18).await),
+        CommandId::XOB19Interrupt => Reply::Acknowledge(handler.trigger_interrupt(station, Interrupt::Xob19).await),
+        CommandId::ReadDataBlock => {
+            let req = ReadDataBlockRequest::decode_from_bytes(&req.body)?;
+            validate(req.offset, req.length as usize, DATA_BLOCK_MAX_REQUEST_LEN)?;
+            match handler.read_data_block(station, req.block, req.offset, req.length).await {
+                Ok(values) => ReadDataBlockResponse { values: values.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::ReadDBX => {
+            let req = ReadDataBlockRequest::decode_from_bytes(&req.body)?;
+            validate(req.offset, req.length as usize, DATA_BLOCK_MAX_REQUEST_LEN)?;
+            match handler.read_dbx(station, req.block, req.offset, req.length).await {
+                Ok(values) => ReadDataBlockResponse { values: values.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::WriteDataBlock => {
+            let req = WriteDataBlockRequest::decode_from_bytes(&req.body)?;
+            validate(req.offset, req.values.len(), DATA_BLOCK_MAX_REQUEST_LEN)?;
+            Reply::Acknowledge(handler.write_data_block(station, req.block, req.offset, req.values.into()).await)
+        }
+        CommandId::MakeDataBlock => {
+            let req = MakeDataBlockRequest::decode_from_bytes(&req.body)?;
+            Reply::Acknowledge(handler.make_data_block(station, req.block, req.length).await)
+        }
+        CommandId::ClearDataBlock => {
+            let req = ClearDataBlockRequest::decode_from_bytes(&req.body)?;
+            Reply::Acknowledge(handler.clear_data_block(station, req.block).await)
+        }
+        CommandId::ReadText => {
+            let req = ReadTextRequest::decode_from_bytes(&req.body)?;
+            validate(req.offset, req.length as usize, TEXT_MAX_REQUEST_LEN)?;
+            match handler.read_text(station, req.text, req.offset, req.length).await {
+                Ok(value) => ReadTextResponse { value: value.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::WriteText => {
+            let req = WriteTextRequest::decode_from_bytes(&req.body)?;
+            validate(req.offset, req.value.chars().count(), TEXT_MAX_REQUEST_LEN)?;
+            Reply::Acknowledge(handler.write_text(station, req.text, req.offset, req.value.into()).await)
+        }
+        CommandId::MakeText => {
+            let req = MakeTextRequest::decode_from_bytes(&req.body)?;
+            Reply::Acknowledge(handler.make_text(station, req.text, req.length).await)
+        }
+        CommandId::ClearText => {
+            let req = ClearTextRequest::decode_from_bytes(&req.body)?;
+            Reply::Acknowledge(handler.clear_text(station, req.text).await)
+        }
+        CommandId::ReadUserMemory => {
+            let req = ReadUserMemoryRequest::decode_from_bytes(&req.body)?;
+            validate_memory(req.address, req.length as usize)?;
+            match handler.read_user_memory(station, req.address, req.length).await {
+                Ok(bytes) => ReadUserMemoryResponse { bytes: bytes.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::WriteUserMemory => {
+            let req = WriteUserMemoryRequest::decode_from_bytes(&req.body)?;
+            validate_memory(req.address, req.bytes.len())?;
+            Reply::Acknowledge(handler.write_user_memory(station, req.address, req.bytes.into()).await)
+        }
+        CommandId::ReadByte => {
+            let req = ReadBytesRequest::decode_from_bytes(&req.body)?;
+            validate_memory(req.address, req.length as usize)?;
+            match handler.read_bytes(station, req.address, req.length).await {
+                Ok(bytes) => ReadBytesResponse { bytes: bytes.into() }.encode_to_bytes().into(),
+                Err(ack) => Reply::Acknowledge(ack),
+            }
+        }
+        CommandId::WriteByte => {
+            let req = WriteBytesRequest::decode_from_bytes(&req.body)?;
+            validate_memory(req.address, req.bytes.len())?;
+            Reply::Acknowledge(handler.write_bytes(station, req.address, req.bytes.into()).await)
+        }
+        command_id => match dispatch_cpu_command(handler, station, command_id).await {
+            Some(reply) => reply,
+            // Commands without a handler method are refused like unknown commands
+            None => Reply::Acknowledge(Acknowledge::Nak),
+        },
+    };
+
+    Ok(reply)
+}
+
+/// Handles the commands that select the CPU with the command id. Returns [`None`] for other commands.
+async fn dispatch_cpu_command<H: SBusHandler>(handler: &H, station: u8, command_id: CommandId) -> Option<Reply> {
+    let ack = match CpuSelection::from_command(command_id)? {
+        (CpuCommand::Run, cpus) => handler.run_cpu(station, cpus).await,
+        (CpuCommand::Stop, cpus) => handler.stop_cpu(station, cpus).await,
+        (CpuCommand::RestartCold, cpus) => handler.restart_cpu_cold(station, cpus).await,
+        (CpuCommand::RestartWarm, cpus) => handler.restart_cpu_warm(station, cpus).await,
+    };
+    Some(Reply::Acknowledge(ack))
+}
@@ -0,0 +1,740 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::Duration,
+};
+
+use crate::{
+    acknowledge::Acknowledge,
+    command_id::CommandId,
+    consts::BROADCAST_STATION,
+    cpu::{Cpu, CpuSelection},
+    interrupt::Interrupt,
+    server::{RequestAction, SBusHandler},
+    RealTimeClock,
+};
+
+pub(crate) const MEDIA_SIZE: usize = u16::MAX as usize + 1;
+const MEMORY_SIZE: usize = 0x10000;
+
+/// A fault the [`SBusSimulator`] applies to a future request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimulatorFault {
+    /// Refuse the request with the acknowledge code.
+    Nak(Acknowledge),
+    /// Drop the request without a reply.
+    Drop,
+}
+
+struct State {
+    station: u8,
+    counters: Vec<i32>,
+    flags: Vec<bool>,
+    inputs: Vec<bool>,
+    outputs: Vec<bool>,
+    registers: Vec<i32>,
+    timers: Vec<i32>,
+    user_eeprom_registers: Vec<i32>,
+    user_memory: Vec<u8>,
+    memory: Vec<u8>,
+    rtc: RealTimeClock,
+    display_register: u32,
+    firmware_version: String,
+    running: bool,
+    halt_failure_register: u32,
+    interrupts: Vec<Interrupt>,
+    data_blocks: HashMap<u16, Vec<i32>>,
+    dbx: HashMap<u16, Vec<i32>>,
+    texts: HashMap<u16, Vec<char>>,
+    latency: Duration,
+    faults: VecDeque<SimulatorFault>,
+}
+
+/// A simulated PCD with a single CPU, to be served by an S-Bus server.
+///
+/// The simulator answers every command the crate supports, from a complete media image, data blocks, texts and the state of its CPU.
+/// It only answers requests addressed to its own station, and executes broadcasts without answering them.
+/// All media start out as zero, the CPU runs and there are no data blocks or texts.
+/// Everything can be preloaded and inspected while the simulator is being served.
+pub struct SBusSimulator {
+    state: Mutex<State>,
+}
+
+impl SBusSimulator {
+    pub fn new(station: u8) -> Self {
+        Self {
+            state: Mutex::new(State {
+                station,
+                counters: vec![0; MEDIA_SIZE],
+                flags: vec![false; MEDIA_SIZE],
+                inputs: vec![false; MEDIA_SIZE],
+                outputs: vec![false; MEDIA_SIZE],
+                registers: vec![0; MEDIA_SIZE],
+                timers: vec![0; MEDIA_SIZE],
+                user_eeprom_registers: vec![0; MEDIA_SIZE],
+                user_memory: vec![0; MEMORY_SIZE],
+                memory: vec![0; MEMORY_SIZE],
+                rtc: RealTimeClock {
+                    week: 1,
+                    week_day: 1,
+                    year: 0,
+                    month: 1,
+                    day: 1,
+                    hour: 0,
+                    minute: 0,
+                    second: 0,
+                },
+                display_register: 0,
+                firmware_version: String::from("SIM"),
+                running: true,
+                halt_failure_register: 0,
+                interrupts: Vec::new(),
+                data_blocks: HashMap::new(),
+                dbx: HashMap::new(),
+                texts: HashMap::new(),
+                latency: Duration::ZERO,
+                faults: VecDeque::new(),
+            }),
+        }
+    }
+
+    pub fn station(&self) -> u8 {
+        self.state.lock().unwrap().station
+    }
+
+    pub fn counters(&self, address: u16, length: usize) -> Vec<i32> {
+        read(&self.state.lock().unwrap().counters, address, length)
+    }
+
+    pub fn set_counters(&self, address: u16, values: &[i32]) {
+        write(&mut self.state.lock().unwrap().counters, address, values);
+    }
+
+    pub fn flags(&self, address: u16, length: usize) -> Vec<bool> {
+        read(&self.state.lock().unwrap().flags, address, length)
+    }
+
+    pub fn set_flags(&self, address: u16, values: &[bool]) {
+        write(&mut self.state.lock().unwrap().flags, address, values);
+    }
+
+    pub fn inputs(&self, address: u16, length: usize) -> Vec<bool> {
+        read(&self.state.lock().unwrap().inputs, address, length)
+    }
+
+    pub fn set_inputs(&self, address: u16, values: &[bool]) {
+        write(&mut self.state.lock().unwrap().inputs, address, values);
+    }
+
+    pub fn outputs(&self, address: u16, length: usize) -> Vec<bool> {
+        read(&self.state.lock().unwrap().outputs, address, length)
+    }
+
+    pub fn set_outputs(&self, address: u16, values: &[bool]) {
+        write(&mut self.state.lock().unwrap().outputs, address, values);
+    }
+
+    pub fn registers(&self, address: u16, length: usize) -> Vec<i32> {
+        read(&self.state.lock().unwrap().registers, address, length)
+    }
+
+    pub fn set_registers(&self, address: u16, values: &[i32]) {
+        write(&mut self.state.lock().unwrap().registers, address, values);
+    }
+
+    pub fn timers(&self, address: u16, length: usize) -> Vec<i32> {
+        read(&self.state.lock().unwrap().timers, address, length)
+    }
+
+    pub fn set_timers(&self, address: u16, values: &[i32]) {
+        write(&mut self.state.lock().unwrap().timers, address, values);
+    }
+
+    pub fn user_eeprom_registers(&self, address: u16, length: usize) -> Vec<i32> {
+        read(&self.state.lock().unwrap().user_eeprom_registers, address, length)
+    }
+
+    pub fn set_user_eeprom_registers(&self, address: u16, values: &[i32]) {
+        write(&mut self.state.lock().unwrap().user_eeprom_registers, address, values);
+    }
+
+    /// Returns bytes of the user program memory, which has a size of 64 KiB.
+    pub fn user_memory(&self, address: u32, length: usize) -> Option<Vec<u8>> {
+        read_memory(&self.state.lock().unwrap().user_memory, address, length)
+    }
+
+    /// Panics if the bytes are not all within the user program memory.
+    pub fn set_user_memory(&self, address: u32, bytes: &[u8]) {
+        assert!(write_memory(&mut self.state.lock().unwrap().user_memory, address, bytes), "Bytes exceed the user program memory");
+    }
+
+    /// Returns bytes at an absolute address of the memory, which has a size of 64 KiB and is separate from the user program memory.
+    pub fn memory(&self, address: u32, length: usize) -> Option<Vec<u8>> {
+        read_memory(&self.state.lock().unwrap().memory, address, length)
+    }
+
+    /// Panics if the bytes are not all within the memory.
+    pub fn set_memory(&self, address: u32, bytes: &[u8]) {
+        assert!(write_memory(&mut self.state.lock().unwrap().memory, address, bytes), "Bytes exceed the memory");
+    }
+
+    pub fn real_time_clock(&self) -> RealTimeClock {
+        self.state.lock().unwrap().rtc
+    }
+
+    pub fn set_real_time_clock(&self, rtc: RealTimeClock) {
+        self.state.lock().unwrap().rtc = rtc;
+    }
+
+    pub fn set_display_register(&self, register: u32) {
+        self.state.lock().unwrap().display_register = register;
+    }
+
+    pub fn set_firmware_version(&self, version: &str) {
+        self.state.lock().unwrap().firmware_version = version.into();
+    }
+
+    /// Returns whether the CPU runs, which is the own CPU and CPU 0 of the simulated PCD.
+    /// The CPU can be run, stopped and restarted over S-Bus.
+    pub fn is_running(&self) -> bool {
+        self.state.lock().unwrap().running
+    }
+
+    pub fn set_running(&self, running: bool) {
+        self.state.lock().unwrap().running = running;
+    }
+
+    pub fn set_halt_failure_register(&self, register: u32) {
+        self.state.lock().unwrap().halt_failure_register = register;
+    }
+
+    /// Returns the interrupts triggered so far, oldest first.
+    pub fn interrupts(&self) -> Vec<Interrupt> {
+        self.state.lock().unwrap().interrupts.clone()
+    }
+
+    /// Returns the elements of a data block, or [`None`] if the data block does not exist.
+    pub fn data_block(&self, block: u16) -> Option<Vec<i32>> {
+        self.state.lock().unwrap().data_blocks.get(&block).cloned()
+    }
+
+    /// Creates or replaces a data block with the given elements.
+    pub fn set_data_block(&self, block: u16, values: &[i32]) {
+        self.state.lock().unwrap().data_blocks.insert(block, values.to_vec());
+    }
+
+    /// Creates or replaces an extended data block (DBX) with the given elements. A DBX can not be written over S-Bus.
+    pub fn set_dbx(&self, block: u16, values: &[i32]) {
+        self.state.lock().unwrap().dbx.insert(block, values.to_vec());
+    }
+
+    /// Returns the characters of a text, or [`None`] if the text does not exist.
+    pub fn text(&self, text: u16) -> Option<String> {
+        self.state.lock().unwrap().texts.get(&text).map(|chars| chars.iter().collect())
+    }
+
+    /// Creates or replaces a text. The text must only contain characters of ISO 8859-1 to be read over S-Bus.
+    pub fn set_text(&self, text: u16, value: &str) {
+        self.state.lock().unwrap().texts.insert(text, value.chars().collect());
+    }
+
+    /// Delays every reply by the given duration.
+    pub fn set_latency(&self, latency: Duration) {
+        self.state.lock().unwrap().latency = latency;
+    }
+
+    /// Queues a fault. Each request addressed to this station consumes the oldest queued fault.
+    pub fn inject_fault(&self, fault: SimulatorFault) {
+        self.state.lock().unwrap().faults.push_back(fault);
+    }
+
+    /// Runs or stops the CPU if it is selected, and refuses selections of CPUs the simulated PCD does not have.
+    fn set_selected_running(&self, cpus: CpuSelection, running: bool) -> Acknowledge {
+        match cpus {
+            CpuSelection::All | CpuSelection::Cpu(Cpu::Own | Cpu::Cpu0) => {
+                self.set_running(running);
+                Acknowledge::Ack
+            }
+            _ => Acknowledge::Nak,
+        }
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut State) -> T) -> T {
+        f(&mut self.state.lock().unwrap())
+    }
+}
+
+fn read<T: Copy>(media: &[T], address: u16, length: usize) -> Vec<T> {
+    let start = address as usize;
+    media[start..usize::min(start + length, MEDIA_SIZE)].to_vec()
+}
+
+fn write<T: Copy>(media: &mut [T], address: u16, values: &[T]) {
+    let start = address as usize;
+    let end = usize::min(start + values.len(), MEDIA_SIZE);
+    media[start..end].copy_from_slice(&values[..end - start]);
+}
+
+/// Reads bytes of a memory. Returns [`None`] if the bytes are not all within the memory.
+fn read_memory(memory: &[u8], address: u32, length: usize) -> Option<Vec<u8>> {
+    let start = address as usize;
+    memory.get(start..start.checked_add(length)?).map(<[u8]>::to_vec)
+}
+
+/// Writes bytes of a memory. Returns false without writing if the bytes are not all within the memory.
+fn write_memory(memory: &mut [u8], address: u32, bytes: &[u8]) -> bool {
+    let start = address as usize;
+    match start.checked_add(bytes.len()).and_then(|end| memory.get_mut(start..end)) {
+        Some(memory) => {
+            memory.copy_from_slice(bytes);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Reads elements of a data block or characters of a text. Reads of a missing block or past its end are refused.
+fn read_block<T: Copy>(blocks: &HashMap<u16, Vec<T>>, block: u16, offset: u16, length: usize) -> Result<Vec<T>, Acknowledge> {
+    let start = offset as usize;
+    blocks.get(&block).and_then(|values| values.get(start..start + length)).map(<[T]>::to_vec).ok_or(Acknowledge::Nak)
+}
+
+/// Writes elements of a data block or characters of a text. Writes to a missing block or past its end are refused.
+fn write_block<T: Copy>(blocks: &mut HashMap<u16, Vec<T>>, block: u16, offset: u16, values: &[T]) -> Acknowledge {
+    let start = offset as usize;
+    match blocks.get_mut(&block).and_then(|block| block.get_mut(start..start + values.len())) {
+        Some(elements) => {
+            elements.copy_from_slice(values);
+            Acknowledge::Ack
+        }
+        None => Acknowledge::Nak,
+    }
+}
+
+impl SBusHandler for SBusSimulator {
+    async fn filter_request(&self, station: u8, command_id: CommandId) -> RequestAction {
+        let addressed = self.with_state(|state| {
+            (station == state.station || station == BROADCAST_STATION || command_id == CommandId::ReadSBusStationNumber).then(|| (state.latency, state.faults.pop_front()))
+        });
+        let Some((latency, fault)) = addressed else {
+            return RequestAction::Ignore;
+        };
+
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+
+        match fault {
+            None => RequestAction::Dispatch,
+            Some(SimulatorFault::Nak(ack)) => RequestAction::Refuse(ack),
+            Some(SimulatorFault::Drop) => RequestAction::Ignore,
+        }
+    }
+
+    async fn read_real_time_clock(&self, _station: u8) -> Result<RealTimeClock, Acknowledge> {
+        Ok(self.real_time_clock())
+    }
+
+    async fn read_display_register(&self, _station: u8) -> Result<u32, Acknowledge> {
+        Ok(self.with_state(|state| state.display_register))
+    }
+
+    async fn read_firmware_version(&self, _station: u8) -> Result<String, Acknowledge> {
+        Ok(self.with_state(|state| state.firmware_version.clone()))
+    }
+
+    async fn read_sbus_station_number(&self) -> Result<u8, Acknowledge> {
+        Ok(self.station())
+    }
+
+    async fn read_halt_failure_register(&self, _station: u8) -> Result<u32, Acknowledge> {
+        Ok(self.with_state(|state| state.halt_failure_register))
+    }
+
+    async fn read_counters(&self, _station: u8, address: u16, length: u8) -> Result<Vec<i32>, Acknowledge> {
+        Ok(self.counters(address, length as usize))
+    }
+
+    async fn read_flags(&self, _station: u8, address: u16, length: u8) -> Result<Vec<bool>, Acknowledge> {
+        Ok(self.flags(address, length as usize))
+    }
+
+    async fn read_inputs(&self, _station: u8, address: u16, length: u8) -> Result<Vec<bool>, Acknowledge> {
+        Ok(self.inputs(address, length as usize))
+    }
+
+    async fn read_outputs(&self, _station: u8, address: u16, length: u8) -> Result<Vec<bool>, Acknowledge> {
+        Ok(self.outputs(address, length as usize))
+    }
+
+    async fn read_registers(&self, _station: u8, address: u16, length: u8) -> Result<Vec<i32>, Acknowledge> {
+        Ok(self.registers(address, length as usize))
+    }
+
+    async fn read_timers(&self, _station: u8, address: u16, length: u8) -> Result<Vec<i32>, Acknowledge> {
+        Ok(self.timers(address, length as usize))
+    }
+
+    async fn read_user_eeprom_registers(&self, _station: u8, address: u16, length: u8) -> Result<Vec<i32>, Acknowledge> {
+        Ok(self.user_eeprom_registers(address, length as usize))
+    }
+
+    async fn write_real_time_clock(&self, _station: u8, rtc: RealTimeClock) -> Acknowledge {
+        self.set_real_time_clock(rtc);
+        Acknowledge::Ack
+    }
+
+    async fn write_counters(&self, _station: u8, address: u16, values: Vec<i32>) -> Acknowledge {
+        self.set_counters(address, &values);
+        Acknowledge::Ack
+    }
+
+    async fn write_flags(&self, _station: u8, address: u16, values: Vec<bool>) -> Acknowledge {
+        self.set_flags(address, &values);
+        Acknowledge::Ack
+    }
+
+    async fn write_outputs(&self, _station: u8, address: u16, values: Vec<bool>) -> Acknowledge {
+        self.set_outputs(address, &values);
+        Acknowledge::Ack
+    }
+
+    async fn write_registers(&self, _station: u8, address: u16, values: Vec<i32>) -> Acknowledge {
+        self.set_registers(address, &values);
+        Acknowledge::Ack
+    }
+
+    async fn write_timers(&self, _station: u8, address: u16, values: Vec<i32>) -> Acknowledge {
+        self.set_timers(address, &values);
+        Acknowledge::Ack
+    }
+
+    async fn write_user_eeprom_registers(&self, _station: u8, address: u16, values: Vec<i32>) -> Acknowledge {
+        self.set_user_eeprom_registers(address, &values);
+        Acknowledge::Ack
+    }
+
+    async fn clear_all_fort(&self, _station: u8) -> Acknowledge {
+        self.with_state(|state| {
+            state.flags.fill(false);
+            state.outputs.fill(false);
+            state.registers.fill(0);
+            state.timers.fill(0);
+        });
+        Acknowledge::Ack
+    }
+
+    async fn clear_flags(&self, _station: u8) -> Acknowledge {
+        self.with_state(|state| state.flags.fill(false));
+        Acknowledge::Ack
+    }
+
+    async fn clear_outputs(&self, _station: u8) -> Acknowledge {
+        self.with_state(|state| state.outputs.fill(false));
+        Acknowledge::Ack
+    }
+
+    async fn clear_registers(&self, _station: u8) -> Acknowledge {
+        self.with_state(|state| state.registers.fill(0));
+        Acknowledge::Ack
+    }
+
+    async fn clear_timers(&self, _station: u8) -> Acknowledge {
+        self.with_state(|state| state.timers.fill(0));
+        Acknowledge::Ack
+    }
+
+    async fn clear_history(&self, _station: u8) -> Acknowledge {
+        self.with_state(|state| state.halt_failure_register = 0);
+        Acknowledge::Ack
+    }
+
+    async fn trigger_interrupt(&self, _station: u8, interrupt: Interrupt) -> Acknowledge {
+        self.with_state(|state| state.interrupts.push(interrupt));
+        Acknowledge::Ack
+    }
+
+    async fn read_user_memory(&self, _station: u8, address: u32, length: u8) -> Result<Vec<u8>, Acknowledge> {
+        self.user_memory(address, length as usize).ok_or(Acknowledge::Nak)
+    }
+
+    async fn write_user_memory(&self, _station: u8, address: u32, bytes: Vec<u8>) -> Acknowledge {
+        match self.with_state(|state| write_memory(&mut state.user_memory, address, &bytes)) {
+            true => Acknowledge::Ack,
+            false => Acknowledge::Nak,
+        }
+    }
+
+    async fn read_bytes(&self, _station: u8, address: u32, length: u8) -> Result<Vec<u8>, Acknowledge> {
+        self.memory(address, length as usize).ok_or(Acknowledge::Nak)
+    }
+
+    async fn write_bytes(&self, _station: u8, address: u32, bytes: Vec<u8>) -> Acknowledge {
+        match self.with_state(|state| write_memory(&mut state.memory, address, &bytes)) {
+            true => Acknowledge::Ack,
+            false => Acknowledge::Nak,
+        }
+    }
+
+    async fn run_cpu(&self, _station: u8, cpus: CpuSelection) -> Acknowledge {
+        self.set_selected_running(cpus, true)
+    }
+
+    async fn stop_cpu(&self, _station: u8, cpus: CpuSelection) -> Acknowledge {
+        self.set_selected_running(cpus, false)
+    }
+
+    async fn restart_cpu_cold(&self, _station: u8, cpus: CpuSelection) -> Acknowledge {
+        self.set_selected_running(cpus, true)
+    }
+
+    async fn restart_cpu_warm(&self, station: u8, cpus: CpuSelection) -> Acknowledge {
+        self.restart_cpu_cold(station, cpus).await
+    }
+
+    async fn read_data_block(&self, _station: u8, block: u16, offset: u16, length: u8) -> Result<Vec<i32>, Acknowledge> {
+        self.with_state(|state| read_block(&state.data_blocks, block, offset, length as usize))
+    }
+
+    async fn read_dbx(&self, _station: u8, block: u16, offset: u16, length: u8) -> Result<Vec<i32>, Acknowledge> {
+        self.with_state(|state| read_block(&state.dbx, block, offset, length as usize))
+    }
+
+    async fn write_data_block(&self, _station: u8, block: u16, offset: u16, values: Vec<i32>) -> Acknowledge {
+        self.with_state(|state| write_block(&mut state.data_blocks, block, offset, &values))
+    }
+
+    async fn make_data_block(&self, _station: u8, block: u16, length: u16) -> Acknowledge {
+        self.with_state(|state| state.data_blocks.insert(block, vec![0; length as usize]));
+        Acknowledge::Ack
+    }
+
+    async fn clear_data_block(&self, _station: u8, block: u16) -> Acknowledge {
+        self.with_state(|state| match state.data_blocks.get_mut(&block) {
+            Some(values) => {
+                values.fill(0);
+                Acknowledge::Ack
+            }
+            None => Acknowledge::Nak,
+        })
+    }
+
+    async fn read_text(&self, _station: u8, text: u16, offset: u16, length: u8) -> Result<String, Acknowledge> {
+        self.with_state(|state| read_block(&state.texts, text, offset, length as usize)).map(|chars| chars.into_iter().collect())
+    }
+
+    async fn write_text(&self, _station: u8, text: u16, offset: u16, value: String) -> Acknowledge {
+        let chars: Vec<char> = value.chars().collect();
+        self.with_state(|state| write_block(&mut state.texts, text, offset, &chars))
+    }
+
+    async fn make_text(&self, _station: u8, text: u16, length: u16) -> Acknowledge {
+        self.with_state(|state| state.texts.insert(text, vec![' '; length as usize]));
+        Acknowledge::Ack
+    }
+
+    async fn clear_text(&self, _station: u8, text: u16) -> Acknowledge {
+        self.with_state(|state| match state.texts.get_mut(&text) {
+            Some(chars) => {
+                chars.fill(' ');
+                Acknowledge::Ack
+            }
+            None => Acknowledge::Nak,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::UdpSocket;
+
+    use super::*;
+    use crate::{
+        client::{RetryPolicy, SBusClient, SBusError},
+        dangerous::SBusDangerousClient,
+        media::{Media, MediaValues},
+        multimedia::{MultimediaRequest, MultimediaResult},
+        udp_client::SBusUDPClient,
+        udp_server::SBusUDPServer,
+    };
+
+    /// Serves a simulator on a local socket and returns a client connected to it.
+    async fn serve(station: u8) -> (SBusUDPServer<SBusSimulator>, SBusUDPClient) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap();
+        let (server, _) = SBusUDPServer::new(socket, SBusSimulator::new(station));
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        socket.connect(address).await.unwrap();
+        let (client, _) = SBusUDPClient::new(socket);
+        (server, client)
+    }
+
+    #[tokio::test]
+    async fn scripted_station() {
+        let (server, client) = serve(3).await;
+        let simulator = server.handler();
+        client.set_retry_policy(RetryPolicy {
+            timeout: Duration::from_millis(50),
+            retries: 0,
+        });
+
+        simulator.set_registers(100, &[1, 2, 3]);
+        assert_eq!(client.read_registers(3, 100, 3).await.unwrap(), vec![1, 2, 3]);
+
+        client.write_flags(3, 5, &[true, true]).await.unwrap();
+        assert_eq!(simulator.flags(4, 4), vec![false, true, true, false]);
+
+        assert_eq!(client.read_sbus_station_number().await.unwrap(), 3);
+        assert!(matches!(client.read_registers(4, 100, 1).await, Err(SBusError::Timeout)));
+
+        simulator.inject_fault(SimulatorFault::Nak(Acknowledge::NakPassword));
+        simulator.inject_fault(SimulatorFault::Drop);
+        assert!(matches!(client.write_registers(3, 0, &[1]).await, Err(SBusError::Nak(Acknowledge::NakPassword))));
+        assert!(matches!(client.write_registers(3, 0, &[1]).await, Err(SBusError::Timeout)));
+        client.write_registers(3, 0, &[1]).await.unwrap();
+
+        client.clear_registers(3).await.unwrap();
+        assert_eq!(simulator.registers(0, 1), vec![0]);
+        assert_eq!(simulator.registers(100, 3), vec![0, 0, 0]);
+        assert_eq!(simulator.flags(5, 2), vec![true, true]);
+
+        client.write_user_eeprom_registers(3, 10, &[-5, 6]).await.unwrap();
+        assert_eq!(client.read_user_eeprom_registers(3, 9, 3).await.unwrap(), vec![0, -5, 6]);
+        assert_eq!(simulator.registers(10, 2), vec![0, 0]);
+
+        client.trigger_interrupt(3, Interrupt::Xob18).await.unwrap();
+        assert_eq!(simulator.interrupts(), vec![Interrupt::Xob18]);
+    }
+
+    #[tokio::test]
+    async fn multimedia() {
+        let (server, client) = serve(0).await;
+        let simulator = server.handler();
+
+        simulator.set_registers(10, &[1, 2, 3]);
+        simulator.set_inputs(7, &[true]);
+
+        let request = MultimediaRequest::new()
+            .read(Media::Registers, 10, 3)
+            .read(Media::Inputs, 6, 2)
+            .write_outputs(4, &[true, false, true])
+            .read(Media::Outputs, 4, 3);
+        let results = client.read_write_multimedia(0, &request).await.unwrap();
+        assert_eq!(
+            results,
+            vec![
+                MultimediaResult::Values(MediaValues::Integers(vec![1, 2, 3])),
+                MultimediaResult::Values(MediaValues::Bools(vec![false, true])),
+                MultimediaResult::Written,
+                MultimediaResult::Values(MediaValues::Bools(vec![true, false, true])),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn broadcast() {
+        let (server, client) = serve(3).await;
+        let simulator = server.handler();
+
+        client.broadcast_write_registers(7, &[5, 6]).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while simulator.registers(7, 2) != vec![5, 6] {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        // The server must not have replied
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(client.discarded_response_count(), 0);
+        assert!(matches!(client.write_registers(BROADCAST_STATION, 7, &[1]).await, Err(SBusError::ArgumentsOutOfRange(_))));
+    }
+
+    #[tokio::test]
+    async fn data_blocks() {
+        let (server, client) = serve(0).await;
+        let simulator = server.handler();
+
+        client.make_data_block(0, 4000, 100).await.unwrap();
+        let values: Vec<i32> = (0..80).collect();
+        client.write_data_block(0, 4000, 10, &values).await.unwrap();
+        assert_eq!(client.read_data_block(0, 4000, 10, 80).await.unwrap(), values);
+        assert_eq!(simulator.data_block(4000).unwrap()[9..12], [0, 0, 1]);
+
+        client.clear_data_block(0, 4000).await.unwrap();
+        assert_eq!(simulator.data_block(4000), Some(vec![0; 100]));
+
+        simulator.set_dbx(5000, &[7, 8, 9]);
+        assert_eq!(client.read_dbx(0, 5000, 1, 2).await.unwrap(), vec![8, 9]);
+
+        assert!(matches!(client.read_data_block(0, 4000, 90, 20).await, Err(SBusError::Nak(Acknowledge::Nak))));
+        assert!(matches!(client.write_data_block(0, 4001, 0, &[1]).await, Err(SBusError::Nak(Acknowledge::Nak))));
+    }
+
+    #[tokio::test]
+    async fn texts() {
+        let (server, client) = serve(0).await;
+        let simulator = server.handler();
+
+        client.make_text(0, 10, 200).await.unwrap();
+        let value: String = "Überdruck ".repeat(15);
+        client.write_text(0, 10, 20, &value).await.unwrap();
+        assert_eq!(client.read_text(0, 10, 20, 150).await.unwrap(), value);
+        assert_eq!(client.read_text(0, 10, 16, 6).await.unwrap(), "    Üb");
+
+        client.clear_text(0, 10).await.unwrap();
+        assert_eq!(simulator.text(10), Some(" ".repeat(200)));
+
+        simulator.set_text(11, "Alarm");
+        assert_eq!(client.read_text(0, 11, 0, 5).await.unwrap(), "Alarm");
+        assert!(matches!(client.read_text(0, 11, 0, 6).await, Err(SBusError::Nak(Acknowledge::Nak))));
+    }
+
+    #[tokio::test]
+    async fn run_stop_and_restart() {
+        let (server, client) = serve(0).await;
+        let simulator = server.handler();
+
+        assert!(simulator.is_running());
+        client.stop_cpu(0, Cpu::Cpu0).await.unwrap();
+        assert!(!simulator.is_running());
+        client.run_cpu(0, CpuSelection::All).await.unwrap();
+        assert!(simulator.is_running());
+        client.stop_cpu(0, Cpu::Own).await.unwrap();
+        assert!(!simulator.is_running());
+        assert!(matches!(client.run_cpu(0, Cpu::Cpu2).await, Err(SBusError::Nak(Acknowledge::Nak))));
+        assert!(!simulator.is_running());
+
+        client.restart_cpu_warm(0, Cpu::Own).await.unwrap();
+        assert!(simulator.is_running());
+    }
+
+    #[tokio::test]
+    async fn halt_failure_register() {
+        let (server, client) = serve(0).await;
+        let simulator = server.handler();
+
+        simulator.set_halt_failure_register(0x20);
+        assert_eq!(client.read_halt_failure_register(0).await.unwrap(), 0x20);
+
+        client.clear_history(0).await.unwrap();
+        assert_eq!(client.read_halt_failure_register(0).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn memory() {
+        let (server, client) = serve(0).await;
+        let simulator = server.handler();
+
+        client.write_user_memory(0, 0x100, &[1, 2, 3]).await.unwrap();
+        assert_eq!(client.read_user_memory(0, 0xFF, 5).await.unwrap(), vec![0, 1, 2, 3, 0]);
+        assert_eq!(simulator.memory(0x100, 3), Some(vec![0, 0, 0]));
+
+        simulator.set_memory(0xFFFE, &[0xAB, 0xCD]);
+        assert_eq!(client.read_bytes(0, 0xFFFE, 2).await.unwrap(), vec![0xAB, 0xCD]);
+        assert!(matches!(client.read_bytes(0, 0xFFFF, 2).await, Err(SBusError::Nak(Acknowledge::Nak))));
+        assert!(matches!(client.write_bytes(0, 0x10000, &[1]).await, Err(SBusError::Nak(Acknowledge::Nak))));
+    }
+}
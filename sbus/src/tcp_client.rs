@@ -0,0 +1,315 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, AtomicU16, Ordering},
+        Arc, Mutex as StdMutex,
+    },
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpStream,
+    },
+    sync::{broadcast, Mutex},
+    task::JoinHandle,
+};
+
+use crate::{
+    client::{response_body, RetryPolicy, SBusClient, SBusError},
+    command_id::CommandId,
+    consts::BROADCAST_STATION,
+    encoding::*,
+    message::*,
+    udp_client::{encode_request, DiscardedResponse, DiscardedResponses, PendingRequest, ResponseMap},
+};
+
+struct Connection {
+    writer: OwnedWriteHalf,
+    reader: JoinHandle<()>,
+    /// Set by the reader before it fails the pending requests, so that they reconnect when they retry.
+    closed: Arc<AtomicBool>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Client for Ether-S-Bus over TCP.
+/// The connection is established on the first request and re-established whenever it is found broken.
+pub struct SBusTCPClient {
+    address: SocketAddr,
+    connection: Mutex<Option<Connection>>,
+    sequence_number: AtomicU16,
+    response_map: ResponseMap,
+    discarded_responses: Arc<DiscardedResponses>,
+    retry_policy: std::sync::Mutex<RetryPolicy>,
+}
+
+impl SBusTCPClient {
+    pub fn new(address: SocketAddr) -> Self {
+        Self {
+            address,
+            connection: Mutex::new(None),
+            sequence_number: AtomicU16::default(),
+            response_map: Arc::new(StdMutex::new(HashMap::new())),
+            discarded_responses: DiscardedResponses::new(),
+            retry_policy: Default::default(),
+        }
+    }
+
+    /// Returns the timeout and retransmission policy used for requests.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        *self.retry_policy.lock().unwrap()
+    }
+
+    /// Sets the timeout and retransmission policy used for all subsequent requests.
+    pub fn set_retry_policy(&self, retry_policy: RetryPolicy) {
+        *self.retry_policy.lock().unwrap() = retry_policy;
+    }
+
+    /// Returns the number of responses discarded since the client was created.
+    pub fn discarded_response_count(&self) -> u64 {
+        self.discarded_responses.count()
+    }
+
+    /// Subscribes to the responses discarded from now on.
+    pub fn subscribe_discarded_responses(&self) -> broadcast::Receiver<DiscardedResponse> {
+        self.discarded_responses.subscribe()
+    }
+
+    /// Writes the telegram to the current connection, connecting first if there is none or it was closed.
+    /// A connection that fails to write is closed like one the reader found broken, so that the next attempt reconnects.
+    async fn send(&self, bytes: &[u8]) -> Result<(), SBusError> {
+        let mut connection = self.connection.lock().await;
+
+        if connection.as_ref().is_none_or(|c| c.closed.load(Ordering::Acquire)) {
+            *connection = None;
+            let (reader, writer) = TcpStream::connect(self.address).await?.into_split();
+            let closed = Arc::new(AtomicBool::new(false));
+            let reader = tokio::spawn(Self::receive_responses(reader, self.response_map.clone(), self.discarded_responses.clone(), closed.clone()));
+            *connection = Some(Connection { writer, reader, closed });
+        }
+
+        let connection = connection.as_mut().unwrap();
+        if let Err(error) = connection.writer.write_all(bytes).await {
+            let error = SBusError::from(error);
+            connection.closed.store(true, Ordering::Release);
+            fail_pending_requests(&self.response_map, &error);
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    async fn receive_responses(mut reader: OwnedReadHalf, response_map: ResponseMap, discarded_responses: Arc<DiscardedResponses>, closed: Arc<AtomicBool>) {
+        async fn recv(reader: &mut OwnedReadHalf) -> Result<Vec<u8>, SBusError> {
+            // The message starts with its total length, including the length itself
+            let byte_length = reader.read_u32().await?;
+            if !(11..=1024).contains(&byte_length) {
+                return Err(SBusError::InvalidResponse("Invalid byte length"));
+            }
+            let mut read_buffer = vec![0; byte_length as usize];
+            read_buffer[..4].copy_from_slice(&byte_length.to_be_bytes());
+            reader.read_exact(&mut read_buffer[4..]).await?;
+            Ok(read_buffer)
+        }
+
+        // Only errors that lose track of the message boundaries close the connection
+        let error = loop {
+            let read_buffer = match recv(&mut reader).await {
+                Ok(read_buffer) => read_buffer,
+                Err(error) => break error,
+            };
+
+            let msg = match Message::decode_from_bytes(&read_buffer) {
+                Ok(msg) => msg,
+                Err(error) => {
+                    discarded_responses.report(DiscardedResponse::Invalid(error.into()));
+                    continue;
+                }
+            };
+
+            let sender = response_map.lock().unwrap().remove(&msg.sequence_number);
+            match sender {
+                None => discarded_responses.report(DiscardedResponse::Unexpected {
+                    sequence_number: msg.sequence_number,
+                }),
+                Some(sender) => _ = sender.send(Ok(msg)),
+            }
+        };
+
+        closed.store(true, Ordering::Release);
+        fail_pending_requests(&response_map, &error);
+    }
+}
+
+/// Fails all requests waiting for a response on a closed connection with the error that closed it.
+fn fail_pending_requests(response_map: &ResponseMap, error: &SBusError) {
+    let mut response_map = response_map.lock().unwrap();
+    for (_, sender) in response_map.drain() {
+        _ = sender.send(Err(error.clone()));
+    }
+}
+
+impl SBusClient for SBusTCPClient {
+    async fn send_request(&self, station: u8, command_id: CommandId, body: Vec<u8>, response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
+        if station == BROADCAST_STATION {
+            return Err(SBusError::ArgumentsOutOfRange("Broadcasts are not replied to"));
+        }
+
+        let (sequence_number, req_bytes) = encode_request(&self.sequence_number, station, command_id, body)?;
+
+        let pending = PendingRequest::new(&self.response_map, sequence_number);
+        let mut receiver = pending.receiver();
+
+        let retry_policy = self.retry_policy();
+        let mut retries = 0;
+
+        let res_msg = loop {
+            let error = match self.send(&req_bytes).await {
+                Ok(()) => match tokio::time::timeout(retry_policy.timeout, &mut receiver).await {
+                    Ok(Ok(Ok(msg))) => break msg,
+                    // The connection was closed before the response arrived, the retry reconnects
+                    Ok(Ok(Err(error))) => {
+                        receiver = pending.receiver();
+                        error
+                    }
+                    Ok(Err(_)) => return Err(SBusError::Internal("Too many concurrent requests")),
+                    Err(_) => SBusError::Timeout,
+                },
+                // The failed write also failed this request's receiver
+                Err(error) => {
+                    receiver = pending.receiver();
+                    error
+                }
+            };
+
+            if retries < retry_policy.retries {
+                retries += 1;
+            } else {
+                return Err(error);
+            }
+        };
+
+        response_body(res_msg.telegram_attribute, res_msg.body, response_type)
+    }
+
+    async fn send_broadcast(&self, command_id: CommandId, body: Vec<u8>) -> Result<(), SBusError> {
+        let (_, req_bytes) = encode_request(&self.sequence_number, BROADCAST_STATION, command_id, body)?;
+        self.send(&req_bytes).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::commands::*;
+
+    async fn answer(stream: &mut TcpStream, station: u8) {
+        let byte_length = stream.read_u32().await.unwrap();
+        let mut buffer = vec![0; byte_length as usize];
+        buffer[..4].copy_from_slice(&byte_length.to_be_bytes());
+        stream.read_exact(&mut buffer[4..]).await.unwrap();
+        let req_msg = Message::decode_from_bytes(&buffer).unwrap();
+
+        let res_msg = Message {
+            sequence_number: req_msg.sequence_number,
+            telegram_attribute: TelegramAttribute::Response,
+            body: ReadSBusStationNumberResponse { station }.encode_to_bytes().unwrap(),
+        };
+        let res_bytes = res_msg.encode_to_bytes().unwrap();
+
+        // Split the response to exercise reassembly
+        stream.write_all(&res_bytes[..3]).await.unwrap();
+        stream.flush().await.unwrap();
+        tokio::task::yield_now().await;
+        stream.write_all(&res_bytes[3..]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reassemble_and_reconnect() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = SBusTCPClient::new(listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            answer(&mut stream, 1).await;
+            drop(stream);
+
+            let (mut stream, _) = listener.accept().await.unwrap();
+            answer(&mut stream, 2).await;
+        });
+
+        assert_eq!(client.read_sbus_station_number().await.unwrap(), 1);
+        assert_eq!(client.read_sbus_station_number().await.unwrap(), 2);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn retry_after_close_while_pending() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = SBusTCPClient::new(listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let byte_length = stream.read_u32().await.unwrap();
+            let mut buffer = vec![0; byte_length as usize - 4];
+            stream.read_exact(&mut buffer).await.unwrap();
+            drop(stream);
+
+            let (mut stream, _) = listener.accept().await.unwrap();
+            answer(&mut stream, 3).await;
+        });
+
+        assert_eq!(client.read_sbus_station_number().await.unwrap(), 3);
+        server.await.unwrap();
+
+        client.set_retry_policy(RetryPolicy {
+            retries: 0,
+            ..client.retry_policy()
+        });
+        assert!(matches!(client.read_sbus_station_number().await, Err(SBusError::IO(_))));
+    }
+
+    #[tokio::test]
+    async fn fail_pending_on_write_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = Arc::new(SBusTCPClient::new(listener.local_addr().unwrap()));
+        client.set_retry_policy(RetryPolicy {
+            timeout: std::time::Duration::from_secs(10),
+            retries: 0,
+        });
+
+        // Read the first request, never answer it and keep the connection open
+        let (received, receive) = tokio::sync::oneshot::channel();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let byte_length = stream.read_u32().await.unwrap();
+            let mut buffer = vec![0; byte_length as usize - 4];
+            stream.read_exact(&mut buffer).await.unwrap();
+            received.send(()).unwrap();
+            tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+        });
+
+        let pending = tokio::spawn({
+            let client = client.clone();
+            async move { client.read_sbus_station_number().await }
+        });
+        receive.await.unwrap();
+
+        // Writing fails once the write side is shut down, while the reader still waits for responses
+        client.connection.lock().await.as_mut().unwrap().writer.shutdown().await.unwrap();
+        assert!(matches!(client.read_sbus_station_number().await, Err(SBusError::IO(_))));
+
+        let result = tokio::time::timeout(std::time::Duration::from_secs(1), pending).await.unwrap().unwrap();
+        assert!(matches!(result, Err(SBusError::IO(_))));
+        server.abort();
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU16, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tokio::{
+    net::UdpSocket,
+    sync::{broadcast, oneshot},
+    task::{AbortHandle, JoinHandle},
+};
+
+use crate::{
+    client::{response_body, RetryPolicy, SBusClient, SBusError},
+    command_id::CommandId,
+    consts::BROADCAST_STATION,
+    encoding::*,
+    message::*,
+    request::Request,
+};
+
+pub(crate) type ResponseResult = Result<Message, SBusError>;
+pub(crate) type ResponseMap = Arc<Mutex<HashMap<u16, oneshot::Sender<ResponseResult>>>>;
+
+/// Removes the entry of a request from the response map when the request completes, fails or is dropped.
+pub(crate) struct PendingRequest<'a> {
+    response_map: &'a ResponseMap,
+    sequence_number: u16,
+}
+
+impl<'a> PendingRequest<'a> {
+    pub(crate) fn new(response_map: &'a ResponseMap, sequence_number: u16) -> Self {
+        Self { response_map, sequence_number }
+    }
+
+    /// Registers a new receiver for the response, replacing the previous one.
+    pub(crate) fn receiver(&self) -> oneshot::Receiver<ResponseResult> {
+        let (sender, receiver) = oneshot::channel();
+        self.response_map.lock().unwrap().insert(self.sequence_number, sender);
+        receiver
+    }
+}
+
+impl Drop for PendingRequest<'_> {
+    fn drop(&mut self) {
+        self.response_map.lock().unwrap().remove(&self.sequence_number);
+    }
+}
+
+/// A response the client received but discarded.
+#[derive(Debug, Clone)]
+pub enum DiscardedResponse {
+    /// The response could not be decoded, e.g. because of a checksum mismatch.
+    Invalid(SBusError),
+    /// No request is waiting for the sequence number, e.g. a late reply to a request that timed out.
+    Unexpected { sequence_number: u16 },
+}
+
+/// Counts discarded responses and reports them to the subscribers.
+pub(crate) struct DiscardedResponses {
+    count: AtomicU64,
+    sender: broadcast::Sender<DiscardedResponse>,
+}
+
+impl DiscardedResponses {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self {
+            count: AtomicU64::default(),
+            sender: broadcast::channel(16).0,
+        })
+    }
+
+    pub(crate) fn report(&self, response: DiscardedResponse) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        _ = self.sender.send(response);
+    }
+
+    pub(crate) fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<DiscardedResponse> {
+        self.sender.subscribe()
+    }
+}
+
+pub struct SBusUDPClient {
+    socket: Arc<UdpSocket>,
+    sequence_number: AtomicU16,
+    response_map: ResponseMap,
+    discarded_responses: Arc<DiscardedResponses>,
+    retry_policy: std::sync::Mutex<RetryPolicy>,
+    abort_handle: AbortHandle,
+}
+
+impl SBusUDPClient {
+    pub fn new(socket: UdpSocket) -> (Self, JoinHandle<Result<(), SBusError>>) {
+        let socket = Arc::new(socket);
+        let response_map = Arc::new(Mutex::new(HashMap::new()));
+        let discarded_responses = DiscardedResponses::new();
+
+        let join_handle = tokio::spawn(Self::receive_response(socket.clone(), response_map.clone(), discarded_responses.clone()));
+
+        let client = Self {
+            socket,
+            sequence_number: AtomicU16::default(),
+            response_map,
+            discarded_responses,
+            retry_policy: Default::default(),
+            abort_handle: join_handle.abort_handle(),
+        };
+
+        (client, join_handle)
+    }
+
+    /// Returns the timeout and retransmission policy used for requests.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        *self.retry_policy.lock().unwrap()
+    }
+
+    /// Sets the timeout and retransmission policy used for all subsequent requests.
+    pub fn set_retry_policy(&self, retry_policy: RetryPolicy) {
+        *self.retry_policy.lock().unwrap() = retry_policy;
+    }
+
+    /// Returns the number of responses discarded since the client was created.
+    pub fn discarded_response_count(&self) -> u64 {
+        self.discarded_responses.count()
+    }
+
+    /// Subscribes to the responses discarded from now on.
+    pub fn subscribe_discarded_responses(&self) -> broadcast::Receiver<DiscardedResponse> {
+        self.discarded_responses.subscribe()
+    }
+
+    async fn receive_response(socket: Arc<UdpSocket>, response_map: ResponseMap, discarded_responses: Arc<DiscardedResponses>) -> Result<(), SBusError> {
+        let mut read_buffer = [0; 256];
+        loop {
+            let byte_length = match socket.recv(&mut read_buffer).await {
+                Ok(byte_length) => byte_length,
+                Err(error) => {
+                    let error = SBusError::from(error);
+                    let mut response_map = response_map.lock().unwrap();
+                    for (_, sender) in response_map.drain() {
+                        _ = sender.send(Err(error.clone()));
+                    }
+                    return Err(error);
+                }
+            };
+
+            let msg = match Message::decode_from_bytes(&read_buffer[0..byte_length]) {
+                Ok(msg) => msg,
+                Err(error) => {
+                    discarded_responses.report(DiscardedResponse::Invalid(error.into()));
+                    continue;
+                }
+            };
+
+            let sender = response_map.lock().unwrap().remove(&msg.sequence_number);
+            match sender {
+                None => discarded_responses.report(DiscardedResponse::Unexpected {
+                    sequence_number: msg.sequence_number,
+                }),
+                Some(sender) => _ = sender.send(Ok(msg)),
+            }
+        }
+    }
+}
+
+impl SBusClient for SBusUDPClient {
+    async fn send_request(&self, station: u8, command_id: CommandId, body: Vec<u8>, response_type: TelegramAttribute) -> Result<Vec<u8>, SBusError> {
+        if station == BROADCAST_STATION {
+            return Err(SBusError::ArgumentsOutOfRange("Broadcasts are not replied to"));
+        }
+
+        let (sequence_number, req_bytes) = encode_request(&self.sequence_number, station, command_id, body)?;
+
+        let pending = PendingRequest::new(&self.response_map, sequence_number);
+        let mut receiver = pending.receiver();
+
+        let retry_policy = self.retry_policy();
+        let mut retries = 0;
+
+        // Retransmissions reuse the sequence number, so whichever transmission is answered first completes the request.
+        let result = loop {
+            self.socket.send(&req_bytes).await?;
+
+            match tokio::time::timeout(retry_policy.timeout, &mut receiver).await {
+                Ok(result) => break result,
+                Err(_) if retries < retry_policy.retries => retries += 1,
+                Err(_) => return Err(SBusError::Timeout),
+            }
+        };
+
+        let res_msg = match result {
+            Ok(Ok(msg)) => msg,
+            Ok(Err(error)) => return Err(error),
+            Err(_err) => return Err(SBusError::Internal("Too many concurrent requests")),
+        };
+
+        response_body(res_msg.telegram_attribute, res_msg.body, response_type)
+    }
+
+    async fn send_broadcast(&self, command_id: CommandId, body: Vec<u8>) -> Result<(), SBusError> {
+        let (_, req_bytes) = encode_request(&self.sequence_number, BROADCAST_STATION, command_id, body)?;
+        self.socket.send(&req_bytes).await?;
+        Ok(())
+    }
+}
+
+/// Encodes a request into a telegram with the next sequence number.
+pub(crate) fn encode_request(sequence_number: &AtomicU16, station: u8, command_id: CommandId, body: Vec<u8>) -> Result<(u16, Vec<u8>), SBusError> {
+    let sequence_number = sequence_number.fetch_add(1, Ordering::Relaxed);
+
+    let req = Request {
+        station,
+        command_id,
+        body: body.into(),
+    };
+
+    let req_msg = Message {
+        sequence_number,
+        telegram_attribute: TelegramAttribute::Request,
+        body: req.encode_to_bytes()?,
+    };
+
+    Ok((sequence_number, req_msg.encode_to_bytes()?))
+}
+
+impl Drop for SBusUDPClient {
+    fn drop(&mut self) {
+        self.abort_handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::commands::*;
+
+    async fn socket_pair() -> (UdpSocket, UdpSocket) {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        client.connect(server.local_addr().unwrap()).await.unwrap();
+        server.connect(client.local_addr().unwrap()).await.unwrap();
+        (server, client)
+    }
+
+    #[tokio::test]
+    async fn retransmit_with_same_sequence_number() {
+        let (server, socket) = socket_pair().await;
+        let (client, _) = SBusUDPClient::new(socket);
+        client.set_retry_policy(RetryPolicy {
+            timeout: Duration::from_millis(50),
+            retries: 1,
+        });
+
+        let server = tokio::spawn(async move {
+            let mut buffer = [0; 256];
+            let length = server.recv(&mut buffer).await.unwrap();
+            let first = Message::decode_from_bytes(&buffer[..length]).unwrap();
+            let length = server.recv(&mut buffer).await.unwrap();
+            let second = Message::decode_from_bytes(&buffer[..length]).unwrap();
+            assert_eq!(first, second);
+
+            let res = Message {
+                sequence_number: second.sequence_number,
+                telegram_attribute: TelegramAttribute::Response,
+                body: ReadSBusStationNumberResponse { station: 7 }.encode_to_bytes().unwrap(),
+            };
+            server.send(&res.encode_to_bytes().unwrap()).await.unwrap();
+        });
+
+        assert_eq!(client.read_sbus_station_number().await.unwrap(), 7);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn discard_invalid_and_unexpected_responses() {
+        let (server, socket) = socket_pair().await;
+        let (client, _) = SBusUDPClient::new(socket);
+        let mut discarded = client.subscribe_discarded_responses();
+
+        let server = tokio::spawn(async move {
+            let mut buffer = [0; 256];
+            let length = server.recv(&mut buffer).await.unwrap();
+            let req = Message::decode_from_bytes(&buffer[..length]).unwrap();
+
+            let mut res = Message {
+                sequence_number: req.sequence_number.wrapping_add(100),
+                telegram_attribute: TelegramAttribute::Response,
+                body: ReadSBusStationNumberResponse { station: 7 }.encode_to_bytes().unwrap(),
+            };
+            let mut res_bytes = res.encode_to_bytes().unwrap();
+            server.send(&res_bytes).await.unwrap();
+
+            res.sequence_number = req.sequence_number;
+            res_bytes = res.encode_to_bytes().unwrap();
+            *res_bytes.last_mut().unwrap() ^= 0xFF;
+            server.send(&res_bytes).await.unwrap();
+
+            server.send(&res.encode_to_bytes().unwrap()).await.unwrap();
+        });
+
+        assert_eq!(client.read_sbus_station_number().await.unwrap(), 7);
+        assert_eq!(client.discarded_response_count(), 2);
+        assert!(matches!(discarded.recv().await, Ok(DiscardedResponse::Unexpected { .. })));
+        assert!(matches!(discarded.recv().await, Ok(DiscardedResponse::Invalid(_))));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn timeout_removes_pending_request() {
+        let (_server, socket) = socket_pair().await;
+        let (client, _) = SBusUDPClient::new(socket);
+        client.set_retry_policy(RetryPolicy {
+            timeout: Duration::from_millis(10),
+            retries: 2,
+        });
+
+        assert!(matches!(client.read_display_register(0).await, Err(SBusError::Timeout)));
+        assert!(client.response_map.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn dropped_request_removes_pending_request() {
+        let (_server, socket) = socket_pair().await;
+        let (client, _) = SBusUDPClient::new(socket);
+
+        let request = client.read_display_register(0);
+        assert!(tokio::time::timeout(Duration::from_millis(10), request).await.is_err());
+        assert!(client.response_map.lock().unwrap().is_empty());
+    }
+}
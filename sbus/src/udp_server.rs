@@ -0,0 +1,132 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use tokio::{
+    net::UdpSocket,
+    task::{AbortHandle, JoinHandle},
+};
+
+use crate::{
+    client::SBusError,
+    encoding::*,
+    message::*,
+    request::Request,
+    server::{dispatch, Reply, SBusHandler},
+};
+
+/// Answers Ether-S-Bus requests received on a UDP socket using an [`SBusHandler`].
+/// Every request is handled in its own task, so a slow handler method does not delay other requests.
+pub struct SBusUDPServer<H> {
+    handler: Arc<H>,
+    abort_handle: AbortHandle,
+}
+
+impl<H: SBusHandler> SBusUDPServer<H> {
+    pub fn new(socket: UdpSocket, handler: H) -> (Self, JoinHandle<Result<(), SBusError>>) {
+        let socket = Arc::new(socket);
+        let handler = Arc::new(handler);
+
+        let join_handle = tokio::spawn(Self::receive_requests(socket, handler.clone()));
+
+        let server = Self {
+            handler,
+            abort_handle: join_handle.abort_handle(),
+        };
+
+        (server, join_handle)
+    }
+
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    async fn receive_requests(socket: Arc<UdpSocket>, handler: Arc<H>) -> Result<(), SBusError> {
+        let mut read_buffer = [0; 256];
+        loop {
+            let (byte_length, peer) = socket.recv_from(&mut read_buffer).await?;
+
+            // Datagrams that are not valid requests are ignored like a PCD would
+            let Ok(req_msg) = Message::decode_from_bytes(&read_buffer[0..byte_length]) else {
+                continue;
+            };
+            if req_msg.telegram_attribute != TelegramAttribute::Request {
+                continue;
+            }
+
+            tokio::spawn(Self::handle_request(socket.clone(), handler.clone(), peer, req_msg));
+        }
+    }
+
+    async fn handle_request(socket: Arc<UdpSocket>, handler: Arc<H>, peer: SocketAddr, req_msg: Message) {
+        let Ok(req) = Request::decode_from_bytes(&req_msg.body) else {
+            return;
+        };
+
+        let (telegram_attribute, body) = match dispatch(handler.as_ref(), &req).await {
+            Some(Reply::Response(body)) => (TelegramAttribute::Response, body),
+            Some(Reply::Acknowledge(ack)) => (TelegramAttribute::Acknowledge, ack.encode_to_bytes().unwrap_or_default()),
+            None => return,
+        };
+
+        let res_msg = Message {
+            sequence_number: req_msg.sequence_number,
+            telegram_attribute,
+            body,
+        };
+
+        if let Ok(res_bytes) = res_msg.encode_to_bytes() {
+            _ = socket.send_to(&res_bytes, peer).await;
+        }
+    }
+}
+
+impl<H> Drop for SBusUDPServer<H> {
+    fn drop(&mut self) {
+        self.abort_handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{acknowledge::Acknowledge, client::SBusClient, udp_client::SBusUDPClient};
+
+    #[derive(Default)]
+    struct Registers(Mutex<Vec<i32>>);
+
+    impl SBusHandler for Registers {
+        async fn read_registers(&self, _station: u8, address: u16, length: u8) -> Result<Vec<i32>, Acknowledge> {
+            let registers = self.0.lock().unwrap();
+            let range = address as usize..address as usize + length as usize;
+            registers.get(range).map(|values| values.to_vec()).ok_or(Acknowledge::Nak)
+        }
+
+        async fn write_registers(&self, _station: u8, address: u16, values: Vec<i32>) -> Acknowledge {
+            let mut registers = self.0.lock().unwrap();
+            let end = address as usize + values.len();
+            if registers.len() < end {
+                registers.resize(end, 0);
+            }
+            registers[address as usize..end].copy_from_slice(&values);
+            Acknowledge::Ack
+        }
+    }
+
+    #[tokio::test]
+    async fn client_round_trip() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap();
+        let (server, _) = SBusUDPServer::new(socket, Registers::default());
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        socket.connect(address).await.unwrap();
+        let (client, _) = SBusUDPClient::new(socket);
+
+        client.write_registers(1, 10, &[1, 2, 3]).await.unwrap();
+        assert_eq!(client.read_registers(1, 11, 2).await.unwrap(), vec![2, 3]);
+        assert_eq!(*server.handler().0.lock().unwrap(), vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3]);
+        assert!(matches!(client.read_registers(1, 20, 1).await, Err(SBusError::Nak(Acknowledge::Nak))));
+        assert!(matches!(client.write_flags(1, 0, &[true]).await, Err(SBusError::Nak(Acknowledge::Nak))));
+    }
+}